@@ -5,7 +5,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tauri_plugin_store::StoreExt;
 
-const STORE_PATH: &str = "anti_ban_config.json";
+pub(crate) const STORE_PATH: &str = "anti_ban_config.json";
 
 /// Common browser User-Agent strings
 const USER_AGENTS: &[&str] = &[
@@ -55,6 +55,15 @@ pub struct AntiBanConfig {
     pub min_delay_secs: u64,
     /// Maximum delay in seconds
     pub max_delay_secs: u64,
+    /// Fixed download rate cap passed to `--limit-rate` (e.g. "500K", "2M")
+    ///
+    /// yt-dlp cannot change the rate of a running download, so a gradual
+    /// ramp-up isn't possible; a steady cap is the closest human-like pacing.
+    #[serde(default)]
+    pub limit_rate: Option<String>,
+    /// Speed below which yt-dlp assumes throttling and re-extracts the format URL
+    #[serde(default)]
+    pub throttled_rate: Option<String>,
 }
 
 impl Default for AntiBanConfig {
@@ -64,6 +73,8 @@ impl Default for AntiBanConfig {
             enable_delays: true,
             min_delay_secs: 1,
             max_delay_secs: 5,
+            limit_rate: None,
+            throttled_rate: None,
         }
     }
 }
@@ -102,6 +113,68 @@ impl AntiBanConfig {
             vec![]
         }
     }
+
+    /// Build yt-dlp rate limiting arguments
+    pub fn rate_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(rate) = self.limit_rate.as_deref().filter(|r| !r.is_empty()) {
+            args.push("--limit-rate".to_string());
+            args.push(rate.to_string());
+        }
+        if let Some(rate) = self.throttled_rate.as_deref().filter(|r| !r.is_empty()) {
+            args.push("--throttled-rate".to_string());
+            args.push(rate.to_string());
+        }
+        args
+    }
+}
+
+/// Named pacing presets for switching between networks quickly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AntiBanProfile {
+    /// Own network, in a hurry: no delays, no rate cap
+    Aggressive,
+    /// Everyday pacing
+    Cautious,
+    /// Flagged or shared IP: long pauses and a modest speed cap
+    Stealth,
+}
+
+impl AntiBanProfile {
+    /// Apply this profile's pacing to `base`, keeping settings it doesn't cover
+    pub fn apply(self, base: &AntiBanConfig) -> AntiBanConfig {
+        let (rotate_user_agent, enable_delays, min_delay_secs, max_delay_secs, limit_rate) = match self {
+            AntiBanProfile::Aggressive => (false, false, 0, 0, None),
+            AntiBanProfile::Cautious => (true, true, 3, 10, None),
+            AntiBanProfile::Stealth => (true, true, 15, 45, Some("1M".to_string())),
+        };
+        AntiBanConfig {
+            rotate_user_agent,
+            enable_delays,
+            min_delay_secs,
+            max_delay_secs,
+            limit_rate,
+            throttled_rate: base.throttled_rate.clone(),
+        }
+    }
+}
+
+/// Validate a yt-dlp rate value: a number with an optional K/M/G suffix
+pub fn validate_rate(value: &str) -> Result<(), String> {
+    let digits = value.trim_end_matches(|c: char| matches!(c, 'K' | 'M' | 'G' | 'k' | 'm' | 'g'));
+    let suffix_len = value.len() - digits.len();
+    let valid_number = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.parse::<f64>().map(|n| n > 0.0).unwrap_or(false);
+
+    if suffix_len > 1 || !valid_number {
+        return Err(format!(
+            "Invalid rate \"{}\": use a number with an optional K/M/G suffix, e.g. 500K or 2M",
+            value
+        ));
+    }
+    Ok(())
 }
 
 /// Apply random delay (async)
@@ -125,21 +198,33 @@ pub fn load_config<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AntiBanConfi
         .unwrap_or_default()
 }
 
+/// Saved config, or `profile` applied over it for a single download
+pub fn effective_config<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profile: Option<AntiBanProfile>,
+) -> AntiBanConfig {
+    let config = load_config(app);
+    match profile {
+        Some(profile) => profile.apply(&config),
+        None => config,
+    }
+}
+
 /// Save anti-ban config to store
 pub fn save_config<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     config: &AntiBanConfig,
 ) -> Result<(), String> {
-    let store = app
-        .store(STORE_PATH)
-        .map_err(|e| format!("Failed to open store: {}", e))?;
+    for rate in [&config.limit_rate, &config.throttled_rate].into_iter().flatten() {
+        if !rate.is_empty() {
+            validate_rate(rate)?;
+        }
+    }
 
-    store.set(
+    crate::settings::write_store(
+        app,
+        STORE_PATH,
         "anti_ban",
         serde_json::to_value(config).map_err(|e| format!("Serialization error: {}", e))?,
-    );
-
-    store.save().map_err(|e| format!("Save error: {}", e))?;
-
-    Ok(())
+    )
 }
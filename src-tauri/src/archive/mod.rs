@@ -0,0 +1,112 @@
+//! Download archive management
+//! Wraps yt-dlp's `--download-archive` file of "extractor id" lines
+
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::Manager;
+
+const ARCHIVE_FILE: &str = "download_archive.txt";
+
+/// Settings key for enabling the archive
+pub const USE_ARCHIVE_KEY: &str = "useDownloadArchive";
+
+/// A single recorded download
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveEntry {
+    pub extractor: String,
+    pub id: String,
+}
+
+/// Location of the archive file in the app data dir
+pub fn archive_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(ARCHIVE_FILE))
+        .map_err(|e| format!("App data dir not found: {}", e))
+}
+
+/// Whether downloads should be recorded in (and skipped via) the archive
+pub fn is_enabled<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    crate::settings::get_bool(app, USE_ARCHIVE_KEY, false)
+}
+
+/// Build yt-dlp archive arguments
+pub fn to_ytdlp_args<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<String> {
+    if !is_enabled(app) {
+        return vec![];
+    }
+
+    match archive_path(app) {
+        Ok(path) => {
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            vec![
+                "--download-archive".to_string(),
+                path.to_string_lossy().to_string(),
+            ]
+        }
+        Err(_) => vec![],
+    }
+}
+
+/// Read the archive file, treating a missing file as empty
+fn read_lines<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<Vec<String>, String> {
+    let path = archive_path(app)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    Ok(content
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// List every recorded entry
+pub fn list_entries<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<Vec<ArchiveEntry>, String> {
+    Ok(read_lines(app)?
+        .iter()
+        .filter_map(|line| {
+            let (extractor, id) = line.split_once(' ')?;
+            Some(ArchiveEntry {
+                extractor: extractor.to_string(),
+                id: id.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Remove entries matching `id` (either the bare id or the full "extractor id" line)
+pub fn remove_entry<R: tauri::Runtime>(app: &tauri::AppHandle<R>, id: &str) -> Result<(), String> {
+    let id = id.trim();
+    let lines = read_lines(app)?;
+    let kept: Vec<&String> = lines
+        .iter()
+        .filter(|line| {
+            let entry_id = line.split_once(' ').map(|(_, rest)| rest.trim()).unwrap_or(line.as_str());
+            line.as_str() != id && entry_id != id
+        })
+        .collect();
+
+    if kept.len() == lines.len() {
+        return Err(format!("No archive entry found for \"{}\"", id));
+    }
+
+    let mut content = kept.iter().map(|line| line.as_str()).collect::<Vec<_>>().join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    std::fs::write(archive_path(app)?, content).map_err(|e| e.to_string())
+}
+
+/// Delete the whole archive
+pub fn clear<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    let path = archive_path(app)?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Retry backoff tracking
+//! Puts repeatedly failing URLs on a cooldown so they aren't hammered
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Cooldown after the first failure, doubled for each consecutive failure
+const BASE_COOLDOWN_SECS: u64 = 30;
+/// Upper bound so a URL is never locked out for too long
+const MAX_COOLDOWN_SECS: u64 = 30 * 60;
+
+/// Failure history for a single URL
+struct FailureRecord {
+    failures: u32,
+    retry_at: Instant,
+}
+
+/// In-memory map of URLs that recently failed
+#[derive(Default)]
+pub struct UrlCooldowns {
+    entries: Mutex<HashMap<String, FailureRecord>>,
+}
+
+impl UrlCooldowns {
+    /// Seconds left before `url` may be retried, if it is cooling down
+    pub fn remaining_secs(&self, url: &str) -> Option<u64> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let record = entries.get(url)?;
+        let remaining = record.retry_at.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            None
+        } else {
+            // Round up so the UI never says "0 seconds"
+            Some(remaining.as_secs() + 1)
+        }
+    }
+
+    /// Record a failed attempt and return the new cooldown in seconds
+    ///
+    /// A server-provided `retry_after_secs` replaces the exponential backoff.
+    pub fn record_failure(&self, url: &str, retry_after_secs: Option<u64>) -> u64 {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = entries.get(url).map(|r| r.failures + 1).unwrap_or(1);
+        let cooldown_secs = retry_after_secs
+            .unwrap_or_else(|| BASE_COOLDOWN_SECS.saturating_mul(1u64 << (failures - 1).min(16)))
+            .min(MAX_COOLDOWN_SECS);
+
+        entries.insert(
+            url.to_string(),
+            FailureRecord {
+                failures,
+                retry_at: Instant::now() + Duration::from_secs(cooldown_secs),
+            },
+        );
+        cooldown_secs
+    }
+
+    /// Forget the failure history of `url` after a success
+    pub fn clear(&self, url: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(url);
+    }
+}
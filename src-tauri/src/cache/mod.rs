@@ -0,0 +1,52 @@
+//! In-memory caches
+//! Avoids re-running yt-dlp for metadata that was fetched moments ago
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of info JSON documents kept before the oldest is evicted
+const INFO_CACHE_CAPACITY: usize = 32;
+
+/// Bounded cache of yt-dlp info JSON keyed by URL
+pub struct InfoCache {
+    entries: Mutex<VecDeque<(String, serde_json::Value)>>,
+    capacity: usize,
+}
+
+impl Default for InfoCache {
+    fn default() -> Self {
+        Self::with_capacity(INFO_CACHE_CAPACITY)
+    }
+}
+
+impl InfoCache {
+    /// Create a cache holding at most `capacity` entries (0 disables caching)
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// Get the cached info for `url`
+    pub fn get(&self, url: &str) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .iter()
+            .find(|(key, _)| key == url)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// Cache the info for `url`, evicting the oldest entry when full
+    pub fn insert(&self, url: &str, info: serde_json::Value) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|(key, _)| key != url);
+        entries.push_back((url.to_string(), info));
+        while entries.len() > self.capacity {
+            entries.pop_front();
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Download cancellation
+//! Registry of cancellation tokens for running downloads, keyed by download id
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri_plugin_shell::process::CommandChild;
+use tokio::sync::watch;
+
+/// Cancellation state of one download
+struct Entry {
+    /// yt-dlp process, once spawned
+    child: Option<CommandChild>,
+    cancelled: bool,
+    /// Tells waiters between attempts that the download was cancelled
+    signal: watch::Sender<bool>,
+}
+
+impl Default for Entry {
+    fn default() -> Self {
+        let (signal, _) = watch::channel(false);
+        Self {
+            child: None,
+            cancelled: false,
+            signal,
+        }
+    }
+}
+
+/// Downloads that can currently be cancelled
+#[derive(Default)]
+pub struct ActiveDownloads {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ActiveDownloads {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start tracking a download; it stays cancellable until the guard drops
+    ///
+    /// Tracking starts before yt-dlp is spawned so a cancel that lands during
+    /// the anti-ban delay is not lost. Returns None if a download with this id
+    /// is already running, so its entry is never replaced or removed by another.
+    pub fn track(&self, id: &str) -> Option<TrackedDownload<'_>> {
+        let mut entries = self.lock();
+        if entries.contains_key(id) {
+            return None;
+        }
+        entries.insert(id.to_string(), Entry::default());
+        Some(TrackedDownload {
+            downloads: self,
+            id: id.to_string(),
+        })
+    }
+
+    /// Attach the spawned process; kills it and returns false if already cancelled
+    pub fn register(&self, id: &str, child: CommandChild) -> bool {
+        let mut entries = self.lock();
+        match entries.get_mut(id) {
+            Some(entry) if entry.cancelled => {
+                let _ = child.kill();
+                false
+            }
+            Some(entry) => {
+                entry.child = Some(child);
+                true
+            }
+            None => {
+                entries.insert(
+                    id.to_string(),
+                    Entry {
+                        child: Some(child),
+                        ..Entry::default()
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Cancel a tracked download, killing its process if it has one
+    ///
+    /// Returns false if no download has this id.
+    pub fn cancel(&self, id: &str) -> bool {
+        let child = {
+            let mut entries = self.lock();
+            let Some(entry) = entries.get_mut(id) else {
+                return false;
+            };
+            entry.cancelled = true;
+            entry.signal.send_replace(true);
+            entry.child.take()
+        };
+        if let Some(child) = child {
+            let _ = child.kill();
+        }
+        true
+    }
+
+    /// Whether a download with this id is being tracked
+    pub fn contains(&self, id: &str) -> bool {
+        self.lock().contains_key(id)
+    }
+
+    pub fn is_cancelled(&self, id: &str) -> bool {
+        self.lock().get(id).is_some_and(|entry| entry.cancelled)
+    }
+
+    /// Resolve once the download is cancelled (immediately if it already is)
+    ///
+    /// Never resolves for a download that is not tracked.
+    pub async fn cancelled(&self, id: &str) {
+        let rx = self.lock().get(id).map(|entry| entry.signal.subscribe());
+        match rx {
+            Some(mut rx) => {
+                let _ = rx.wait_for(|cancelled| *cancelled).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Take the process out of the registry, e.g. to kill it directly
+    pub fn take(&self, id: &str) -> Option<CommandChild> {
+        self.lock().get_mut(id).and_then(|entry| entry.child.take())
+    }
+}
+
+/// Keeps a download in the registry; removes it when dropped
+pub struct TrackedDownload<'a> {
+    downloads: &'a ActiveDownloads,
+    id: String,
+}
+
+impl Drop for TrackedDownload<'_> {
+    fn drop(&mut self) {
+        self.downloads.lock().remove(&self.id);
+    }
+}
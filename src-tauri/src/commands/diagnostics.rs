@@ -0,0 +1,576 @@
+//! Diagnostic commands
+//! Quick checks that answer "will this work?" without starting a download
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::commands::download::{
+    classify_failure, validate_url, DownloadFormat, DownloadError, DownloadOptions, LogPayload,
+    OptionOverrides,
+};
+use crate::errors::RecentErrors;
+use crate::logs::LogEntry;
+use crate::proxy::{self, ConnectivityResult};
+use crate::state::AppState;
+
+#[cfg(target_os = "android")]
+use tauri_plugin_ytdlp::YtdlpExt;
+#[cfg(target_os = "android")]
+use tauri_plugin_ytdlp::models as plugin_models;
+
+/// Upper bound for a probe run - a healthy extraction finishes well within this
+#[cfg(not(target_os = "android"))]
+const PROBE_TIMEOUT_SECS: u64 = 20;
+
+/// Upper bound for an argument check - it runs a full extraction
+#[cfg(not(target_os = "android"))]
+const ARG_CHECK_TIMEOUT_SECS: u64 = 60;
+
+/// Number of URLs validated in parallel - enough to be quick, few enough not to look like a flood
+#[cfg(not(target_os = "android"))]
+const BATCH_VALIDATION_CONCURRENCY: usize = 4;
+
+/// Go/no-go verdict for a single URL
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeResult {
+    pub downloadable: bool,
+    pub reason: Option<String>,
+    pub requires_auth: bool,
+    pub is_live: bool,
+}
+
+impl ProbeResult {
+    /// Build a negative verdict from a classified download error
+    fn from_error(error: DownloadError) -> Self {
+        Self {
+            downloadable: false,
+            requires_auth: matches!(error, DownloadError::AuthRequired(_)),
+            reason: Some(error.to_string()),
+            is_live: false,
+        }
+    }
+}
+
+/// Pre-flight verdict for one URL of a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEntry {
+    pub url: String,
+    pub valid: bool,
+    pub title: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl BatchEntry {
+    fn invalid(url: String, error: DownloadError) -> Self {
+        Self {
+            url,
+            valid: false,
+            title: None,
+            reason: Some(error.to_string()),
+        }
+    }
+}
+
+/// Reachability of YouTube over each network path
+#[derive(Debug, Clone, Serialize)]
+pub struct YoutubeConnectivity {
+    pub direct: ConnectivityResult,
+    /// Only present when a proxy is configured
+    pub proxy: Option<ConnectivityResult>,
+}
+
+/// Result of dry-running the download arguments through yt-dlp
+#[derive(Debug, Clone, Serialize)]
+pub struct ArgCheckResult {
+    /// False when yt-dlp rejected the arguments outright
+    pub accepted: bool,
+    /// Deprecation and unknown-option messages reported by yt-dlp
+    pub warnings: Vec<String>,
+}
+
+/// Whether a yt-dlp stderr line complains about the arguments themselves
+#[cfg(not(target_os = "android"))]
+fn is_arg_warning(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.contains("deprecated")
+        || lower.contains("no such option")
+        || lower.contains("unrecognized argument")
+        || lower.contains("ambiguous option")
+}
+
+/// Check whether a URL can be downloaded without downloading it
+#[tauri::command]
+pub async fn probe(url: String, app: tauri::AppHandle) -> Result<ProbeResult, DownloadError> {
+    validate_url(&url)?;
+    app.state::<AppState>()
+        .network
+        .ensure_allowed()
+        .map_err(|_| DownloadError::NetworkStopped)?;
+
+    #[cfg(target_os = "android")]
+    {
+        return Ok(match app.ytdlp().extract_info(plugin_models::ExtractInfoRequest { url }) {
+            Ok(_) => ProbeResult {
+                downloadable: true,
+                reason: None,
+                requires_auth: false,
+                is_live: false,
+            },
+            Err(e) => ProbeResult::from_error(classify_failure(&e.to_string())),
+        });
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use crate::sidecar::{run_sidecar, SidecarType};
+
+        // --print implies --simulate and --quiet, so only the live status is written
+        let mut args = vec![
+            "--no-playlist".to_string(),
+            "--no-warnings".to_string(),
+            "--print".to_string(),
+            "%(live_status)s".to_string(),
+        ];
+
+        let proxy_config = proxy::resolve_proxy_config(&app);
+        if proxy_config.is_enabled() {
+            args.extend(proxy_config.to_ytdlp_args());
+        }
+
+        args.push(url);
+
+        let output = run_sidecar(
+            &app,
+            SidecarType::YtDlp,
+            &args,
+            Some(std::time::Duration::from_secs(PROBE_TIMEOUT_SECS)),
+        )
+        .await
+        .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
+
+        if !output.success() {
+            return Ok(ProbeResult::from_error(classify_failure(&output.stderr)));
+        }
+
+        let live_status = output.stdout.lines().last().unwrap_or("").trim().to_string();
+        let (downloadable, reason) = match live_status.as_str() {
+            "is_live" => (false, Some("Stream is currently live".to_string())),
+            "is_upcoming" => (false, Some("Stream has not started yet".to_string())),
+            _ => (true, None),
+        };
+
+        Ok(ProbeResult {
+            downloadable,
+            reason,
+            requires_auth: false,
+            is_live: live_status == "is_live",
+        })
+    }
+}
+
+/// Test whether YouTube is reachable, directly and through the configured proxy
+#[tauri::command]
+pub async fn test_youtube_connectivity(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<YoutubeConnectivity, String> {
+    state.network.ensure_allowed()?;
+    let direct = proxy::check_endpoint(&state.http_client, proxy::YOUTUBE_204_URL).await;
+
+    let proxy_config = proxy::resolve_proxy_config(&app);
+    let via_proxy = if proxy_config.is_enabled() {
+        let client = proxy::build_proxied_client(&proxy_config)?;
+        Some(proxy::check_endpoint(&client, proxy::YOUTUBE_204_URL).await)
+    } else {
+        None
+    };
+
+    Ok(YoutubeConnectivity {
+        direct,
+        proxy: via_proxy,
+    })
+}
+
+/// Test a proxy config (e.g. unsaved settings) by reaching YouTube through it
+///
+/// A config that can't even be turned into a client (bad host, missing
+/// port) is reported as a failed check rather than a command error.
+#[tauri::command]
+pub async fn test_proxy(
+    config: proxy::ProxyConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<proxy::ConnectivityResult, String> {
+    state.network.ensure_allowed()?;
+    let result = match proxy::build_proxied_client(&config) {
+        Ok(client) => proxy::check_endpoint(&client, proxy::YOUTUBE_204_URL).await,
+        Err(error) => proxy::ConnectivityResult {
+            ok: false,
+            latency_ms: None,
+            error: Some(error),
+        },
+    };
+
+    // Pool rotation skips proxies that failed here until they pass again
+    state.proxy_rotator.mark(&config, result.ok);
+    Ok(result)
+}
+
+/// Check whether the configured proxy can carry QUIC/HTTP3
+///
+/// Explains why a proxy that passes the connectivity test still downloads slowly.
+#[tauri::command]
+pub async fn check_proxy_quic(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<proxy::QuicSupport, String> {
+    state.network.ensure_allowed()?;
+    let config = proxy::resolve_proxy_config(&app);
+    if !config.is_enabled() {
+        return Err("No proxy configured".to_string());
+    }
+    Ok(proxy::check_quic_support(&config).await)
+}
+
+/// Panic stop: abort every download, sidecar install and info fetch in flight
+///
+/// The queue is paused and network commands keep failing until `network_resume`.
+#[tauri::command]
+pub fn network_panic_stop(app: tauri::AppHandle, state: tauri::State<'_, AppState>) {
+    state.network.stop();
+    state.power_pause.forget();
+    state.queue.set_paused(true);
+    let _ = app.emit(
+        "download-log",
+        LogPayload {
+            level: "warn".to_string(),
+            message: "All network activity stopped".to_string(),
+        },
+    );
+}
+
+/// Lift a panic stop; the queue stays paused until resumed explicitly
+#[tauri::command]
+pub fn network_resume(state: tauri::State<'_, AppState>) {
+    state.network.resume();
+}
+
+/// Whether a panic stop is in effect
+#[tauri::command]
+pub fn get_network_stopped(state: tauri::State<'_, AppState>) -> bool {
+    state.network.is_stopped()
+}
+
+/// Dry-run the arguments a download would use and report deprecated or unknown options
+///
+/// Protects against the app passing a flag a newer yt-dlp no longer accepts.
+#[tauri::command]
+pub async fn check_download_args(
+    url: String,
+    format: DownloadFormat,
+    options: Option<OptionOverrides>,
+    app: tauri::AppHandle,
+) -> Result<ArgCheckResult, DownloadError> {
+    validate_url(&url)?;
+    // Check the options a real download of this URL would run with
+    let options = crate::profiles::resolve_options(&app, &url, &options.unwrap_or_default())?;
+
+    #[cfg(target_os = "android")]
+    {
+        // The Android plugin builds its own arguments
+        let _ = (format, options, app);
+        return Ok(ArgCheckResult {
+            accepted: true,
+            warnings: Vec::new(),
+        });
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use crate::commands::download::build_download_args;
+        use crate::sidecar::{run_sidecar, SidecarType};
+
+        let output_template = std::env::temp_dir()
+            .join("%(title)s.%(ext)s")
+            .to_string_lossy()
+            .to_string();
+        let mut args = build_download_args(
+            &app,
+            &url,
+            format,
+            &options,
+            // Peek so the dry run doesn't advance the rotation
+            &crate::proxy::peek_proxy(&app),
+            &output_template,
+            // A dry run isn't a download, so its notes stay out of the log
+            &mut Vec::new(),
+        );
+        args.insert(0, "--simulate".to_string());
+
+        let output = run_sidecar(
+            &app,
+            SidecarType::YtDlp,
+            &args,
+            Some(std::time::Duration::from_secs(ARG_CHECK_TIMEOUT_SECS)),
+        )
+        .await
+        .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
+
+        let warnings: Vec<String> = output
+            .stderr
+            .lines()
+            .filter(|line| is_arg_warning(line))
+            .map(|line| line.trim().to_string())
+            .collect();
+
+        for warning in &warnings {
+            let _ = app.emit(
+                "download-log",
+                LogPayload {
+                    level: "warn".to_string(),
+                    message: warning.clone(),
+                },
+            );
+        }
+
+        // Option parsing errors exit with code 2
+        Ok(ArgCheckResult {
+            accepted: output.code != Some(2),
+            warnings,
+        })
+    }
+}
+
+/// Check every URL of a batch up front, in input order
+///
+/// Metadata fetched here lands in the info cache, so the downloads that
+/// follow start without a second extraction.
+#[tauri::command]
+pub async fn validate_batch(urls: Vec<String>, app: tauri::AppHandle) -> Vec<BatchEntry> {
+    if app.state::<AppState>().network.ensure_allowed().is_err() {
+        return urls
+            .into_iter()
+            .map(|url| BatchEntry::invalid(url, DownloadError::NetworkStopped))
+            .collect();
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        return urls
+            .into_iter()
+            .map(|url| {
+                if let Err(e) = validate_url(&url) {
+                    return BatchEntry::invalid(url, e);
+                }
+                match app.ytdlp().extract_info(plugin_models::ExtractInfoRequest { url: url.clone() }) {
+                    Ok(info) => BatchEntry {
+                        url,
+                        valid: true,
+                        title: Some(info.title),
+                        reason: None,
+                    },
+                    Err(e) => BatchEntry::invalid(url, classify_failure(&e.to_string())),
+                }
+            })
+            .collect();
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use crate::commands::download::fetch_info_json;
+        use futures_util::stream::{self, StreamExt};
+
+        stream::iter(urls)
+            .map(|url| {
+                let app = app.clone();
+                async move {
+                    if let Err(e) = validate_url(&url) {
+                        return BatchEntry::invalid(url, e);
+                    }
+                    match fetch_info_json(&app, &url).await {
+                        Ok(info) => BatchEntry {
+                            title: info.get("title").and_then(|t| t.as_str()).map(String::from),
+                            url,
+                            valid: true,
+                            reason: None,
+                        },
+                        Err(e) => BatchEntry::invalid(url, e),
+                    }
+                }
+            })
+            .buffered(BATCH_VALIDATION_CONCURRENCY)
+            .collect()
+            .await
+    }
+}
+
+/// Get the buffered log entries, oldest first
+#[tauri::command]
+pub fn get_logs(state: tauri::State<'_, AppState>) -> Vec<LogEntry> {
+    state.logs.entries()
+}
+
+/// Get recent download errors with counts per category
+///
+/// `within_secs` limits the result to e.g. the last hour; all kept errors otherwise.
+#[tauri::command]
+pub fn get_recent_errors(
+    within_secs: Option<u64>,
+    state: tauri::State<'_, AppState>,
+) -> RecentErrors {
+    state.recent_errors.recent(within_secs)
+}
+
+/// Get how many log entries are kept in memory
+#[tauri::command]
+pub fn get_log_buffer_size(state: tauri::State<'_, AppState>) -> usize {
+    state.logs.capacity()
+}
+
+/// Resize the log buffer (capped at `logs::MAX_CAPACITY`) and return the applied size
+#[tauri::command]
+pub fn set_log_buffer_size(
+    size: usize,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let applied = state.logs.resize(size);
+    crate::settings::set_value(&app, crate::settings::LOG_BUFFER_SIZE, serde_json::json!(applied))?;
+    Ok(applied)
+}
+
+/// Config layer a setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    /// Options passed with the download itself
+    Call,
+    /// The site profile matching the URL
+    Profile,
+    /// Saved app settings
+    Settings,
+    /// Proxy environment variables
+    Environment,
+    /// Built-in default
+    Default,
+}
+
+/// A resolved value and the layer it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct Annotated<T> {
+    pub value: T,
+    pub source: ConfigSource,
+}
+
+/// Everything a download of one URL would use, with where each part came from
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    pub format: DownloadFormat,
+    /// Host pattern of the site profile that applies
+    pub profile: Option<String>,
+    /// Merged download options
+    pub options: DownloadOptions,
+    /// Source of every download option, by field name
+    pub option_sources: std::collections::BTreeMap<String, ConfigSource>,
+    /// Proxy with the password masked (`None` when connecting directly)
+    pub proxy: Annotated<Option<proxy::ProxyConfig>>,
+    /// Set when downloads rotate through the proxy pool instead
+    pub proxy_rotation: Option<proxy::ProxyRotation>,
+    pub anti_ban: Annotated<crate::anti_ban::AntiBanConfig>,
+    pub sponsorblock: Annotated<crate::sponsorblock::SponsorBlockConfig>,
+    /// Names of the custom headers sent (values are never shown)
+    pub custom_headers: Vec<String>,
+}
+
+/// Resolve every config layer for a URL the way a real download would
+///
+/// Nothing is started and the proxy rotation isn't advanced.
+#[tauri::command]
+pub fn get_effective_config(
+    url: String,
+    format: DownloadFormat,
+    options: Option<OptionOverrides>,
+    app: tauri::AppHandle,
+) -> Result<EffectiveConfig, DownloadError> {
+    validate_url(&url)?;
+    let call = options.unwrap_or_default();
+    let profile = crate::profiles::matching_profile(&app, &url);
+    let merged = crate::profiles::resolve_options(&app, &url, &call)?;
+
+    // A field comes from the call when it was sent, otherwise from the
+    // profile when that sets it to something non-default
+    let as_object = |options: &DownloadOptions| match serde_json::to_value(options) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let defaults = as_object(&DownloadOptions::default());
+    let profile_values = profile.as_ref().map(|p| as_object(&p.options)).unwrap_or_default();
+    let option_sources = defaults
+        .iter()
+        .map(|(key, default)| {
+            let source = if call.contains(key) {
+                ConfigSource::Call
+            } else if profile_values.get(key).is_some_and(|v| v != default) {
+                ConfigSource::Profile
+            } else {
+                ConfigSource::Default
+            };
+            (key.clone(), source)
+        })
+        .collect();
+
+    // Mirrors proxy::resolve_proxy_config, which doesn't say which branch it took
+    let saved_proxy = proxy::load_proxy_config(&app);
+    let proxy_value = if saved_proxy.is_enabled() {
+        Annotated { value: Some(saved_proxy), source: ConfigSource::Settings }
+    } else {
+        match proxy::load_env_fallback_enabled(&app).then(proxy::proxy_from_env).flatten() {
+            Some(env_proxy) => Annotated { value: Some(env_proxy), source: ConfigSource::Environment },
+            None => Annotated { value: None, source: ConfigSource::Default },
+        }
+    };
+    let proxy_value = Annotated {
+        value: proxy_value.value.map(|mut config| {
+            if let Some(auth) = config.auth.as_mut() {
+                if !auth.password.is_empty() {
+                    auth.password = "***".to_string();
+                }
+            }
+            config
+        }),
+        ..proxy_value
+    };
+    let rotation = proxy::load_rotation(&app);
+    let proxy_rotation = (rotation != proxy::ProxyRotation::Off
+        && !proxy::load_proxy_pool(&app).is_empty())
+    .then_some(rotation);
+
+    let anti_ban = Annotated {
+        value: crate::anti_ban::effective_config(&app, merged.anti_ban_profile),
+        source: match option_sources.get("anti_ban_profile") {
+            Some(ConfigSource::Default) | None => ConfigSource::Settings,
+            Some(source) => *source,
+        },
+    };
+    let sponsorblock = Annotated {
+        value: crate::sponsorblock::effective_config(&app, &merged.sponsorblock_remove),
+        source: match option_sources.get("sponsorblock_remove") {
+            Some(ConfigSource::Default) | None => ConfigSource::Settings,
+            Some(source) => *source,
+        },
+    };
+
+    Ok(EffectiveConfig {
+        format,
+        profile: profile.map(|p| p.host_pattern),
+        options: merged,
+        option_sources,
+        proxy: proxy_value,
+        proxy_rotation,
+        anti_ban,
+        sponsorblock,
+        custom_headers: crate::headers::load_headers(&app)
+            .into_iter()
+            .map(|h| h.name)
+            .collect(),
+    })
+}
@@ -10,7 +10,8 @@ use thiserror::Error;
 
 use crate::proxy;
 use crate::safety;
-use crate::sidecar::{get_sidecar_path, SidecarType};
+use crate::sidecar::{get_sidecar_path, is_sidecar_available, SidecarType};
+use crate::state::AppState;
 
 #[cfg(target_os = "android")]
 use tauri_plugin_ytdlp::YtdlpExt;
@@ -22,8 +23,55 @@ static PROGRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"\[download\]\s+(\d+\.?\d*)%").expect("Invalid progress regex")
 });
 
+/// Cached regex for the total size on progress lines ("of ~ 10.24MiB")
+static SIZE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"of\s+~?\s*(\d+\.?\d*)\s*([KMGT]?i?B)").expect("Invalid size regex")
+});
+
+/// Cached regex for yt-dlp's resume notice
+static RESUME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[download\] Resuming download at byte (\d+)").expect("Invalid resume regex")
+});
+
+/// Dates accepted by `--dateafter`/`--datebefore`: YYYYMMDD or relative like `today-7days`
+static DATE_FILTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d{8}|(?:now|today|yesterday)(?:[+-]\d+(?:day|week|month|year)s?)?)$")
+        .expect("Invalid date filter regex")
+});
+
+/// PEM private key header: PKCS#8, RSA, EC or encrypted
+static PEM_PRIVATE_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-----BEGIN (?:[A-Z]+ )?PRIVATE KEY-----").expect("Invalid private key regex")
+});
+
+static RETRY_AFTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)retry[- ]after\W{0,3}(\d+)").expect("Invalid retry-after regex")
+});
+
+/// Regex for the current speed on a progress line, e.g. "at 2.15MiB/s"
+static SPEED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"at\s+(\d+\.?\d*)\s*([KMGT]?i?B)/s").expect("Invalid speed regex")
+});
+
+/// Regex for the time remaining on a progress line, e.g. "ETA 00:45"
+static ETA_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"ETA\s+(\d+(?::\d+){0,2})").expect("Invalid ETA regex")
+});
+
+/// Regex for the format ids yt-dlp settled on, e.g. "Downloading 1 format(s): 137+140"
+static SELECTED_FORMAT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[info\] [^:]+: Downloading \d+ format\(s\): (\S+)")
+        .expect("Invalid selected format regex")
+});
+
+/// Regex for the line yt-dlp prints before each playlist entry
+static PLAYLIST_ITEM_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[download\] Downloading (?:item|video) (\d+) of (\d+)")
+        .expect("Invalid playlist item regex")
+});
+
 /// Audio format options
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AudioFormat {
     Mp3,
@@ -31,23 +79,588 @@ pub enum AudioFormat {
 }
 
 impl AudioFormat {
-    fn as_str(&self) -> &'static str {
+    /// Every supported audio format
+    pub(crate) const ALL: [AudioFormat; 2] = [AudioFormat::Mp3, AudioFormat::Flac];
+
+    pub(crate) fn as_str(&self) -> &'static str {
         match self {
             AudioFormat::Mp3 => "mp3",
             AudioFormat::Flac => "flac",
         }
     }
 
-    fn quality_args(&self) -> Vec<&'static str> {
+    fn quality_args(&self, quality: AudioQuality) -> Vec<&'static str> {
         match self {
-            AudioFormat::Mp3 => vec!["--audio-quality", "0"], // Best quality
+            AudioFormat::Mp3 => vec!["--audio-quality", quality.as_str()],
+            // Lossless, so there is nothing to trade off
             AudioFormat::Flac => vec!["--audio-quality", "0"],
         }
     }
 }
 
-/// Download result returned to frontend
+/// Quality of extracted lossy audio, as yt-dlp's VBR scale (0 best, 10 worst)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioQuality {
+    /// VBR 0, roughly 245 kbps for MP3
+    #[default]
+    Best,
+    /// VBR 3, roughly 175 kbps
+    High,
+    /// VBR 5, roughly 130 kbps
+    Medium,
+    /// VBR 7, roughly 100 kbps
+    Low,
+}
+
+impl AudioQuality {
+    /// Value passed to `--audio-quality`
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            AudioQuality::Best => "0",
+            AudioQuality::High => "3",
+            AudioQuality::Medium => "5",
+            AudioQuality::Low => "7",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            AudioQuality::Best => "best (VBR 0)",
+            AudioQuality::High => "high (VBR 3)",
+            AudioQuality::Medium => "medium (VBR 5)",
+            AudioQuality::Low => "low (VBR 7)",
+        }
+    }
+}
+
+/// Download format: audio extracted from the video, or the video itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadFormat {
+    Mp3,
+    Flac,
+    Mp4,
+    Webm,
+    Mkv,
+}
+
+impl DownloadFormat {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            DownloadFormat::Mp3 => "mp3",
+            DownloadFormat::Flac => "flac",
+            DownloadFormat::Mp4 => "mp4",
+            DownloadFormat::Webm => "webm",
+            DownloadFormat::Mkv => "mkv",
+        }
+    }
+
+    /// The audio format to extract, or None for a video download
+    pub(crate) fn audio(&self) -> Option<AudioFormat> {
+        match self {
+            DownloadFormat::Mp3 => Some(AudioFormat::Mp3),
+            DownloadFormat::Flac => Some(AudioFormat::Flac),
+            DownloadFormat::Mp4 | DownloadFormat::Webm | DownloadFormat::Mkv => None,
+        }
+    }
+
+    pub(crate) fn is_video(&self) -> bool {
+        self.audio().is_none()
+    }
+
+    /// How the container holds an embedded thumbnail, or None when it can't
+    fn thumbnail_embedding(&self) -> Option<ThumbnailEmbedding> {
+        match self {
+            DownloadFormat::Mp3 | DownloadFormat::Flac | DownloadFormat::Mp4 => {
+                Some(ThumbnailEmbedding::CoverArt)
+            }
+            DownloadFormat::Mkv => Some(ThumbnailEmbedding::Attachment),
+            DownloadFormat::Webm => None,
+        }
+    }
+}
+
+/// Ways yt-dlp embeds a thumbnail, depending on the container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThumbnailEmbedding {
+    /// Cover art tag (ID3 APIC, FLAC picture block, MP4 `covr` atom)
+    CoverArt,
+    /// Matroska attachment named `cover.jpg`
+    Attachment,
+}
+
+/// Sample rates accepted for extracted audio
+const SAMPLE_RATES: &[u32] = &[22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000];
+
+/// Highest sample rate the MP3 format supports
+const MP3_MAX_SAMPLE_RATE: u32 = 48000;
+
+/// Subtitle formats downloads can be converted to
+const SUBTITLE_FORMATS: &[&str] = &["srt", "vtt", "ass", "lrc"];
+
+/// Extensions of subtitle files yt-dlp may write
+const SUBTITLE_EXTENSIONS: &[&str] = &[
+    "srt", "vtt", "ass", "lrc", "ttml", "srv1", "srv2", "srv3", "json3",
+];
+
+/// Whether `path` is a subtitle file
+fn is_subtitle_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| SUBTITLE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Extensions of cover art files yt-dlp may write
+const THUMBNAIL_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+
+/// Whether `path` is a cover art file
+fn is_thumbnail_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| THUMBNAIL_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Whether `path` is the downloaded media rather than a file written next to it
+fn is_media_file(path: &std::path::Path) -> bool {
+    !is_subtitle_file(path) && !is_thumbnail_file(path)
+}
+
+/// Naming presets for downloaded files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilenameScheme {
+    #[default]
+    TitleOnly,
+    UploaderTitle,
+    DateTitle,
+    IndexTitle,
+}
+
+impl FilenameScheme {
+    /// yt-dlp output template (relative to the output dir) for this scheme
+    pub fn template(&self) -> &'static str {
+        match self {
+            FilenameScheme::TitleOnly => "%(title)s.%(ext)s",
+            FilenameScheme::UploaderTitle => "%(uploader|Unknown)s - %(title)s.%(ext)s",
+            FilenameScheme::DateTitle => "%(upload_date>%Y-%m-%d|Undated)s - %(title)s.%(ext)s",
+            // Playlist index only when there is one, so single videos stay title-only
+            FilenameScheme::IndexTitle => "%(playlist_index&{:02d} - |)s%(title)s.%(ext)s",
+        }
+    }
+}
+
+/// `Artist/Album/Track.ext` layout for music libraries
+///
+/// Falls back to the uploader and video title for non-music videos. yt-dlp
+/// sanitizes each field, so a `/` in a title can't create extra folders.
+const LIBRARY_TEMPLATE: &str =
+    "%(artist,uploader|Unknown Artist)s/%(album|Unknown Album)s/%(track,title)s.%(ext)s";
+
+/// Check a user filename template and make sure it ends in an extension
+///
+/// The template is used verbatim below the staging dir, so it must stay
+/// relative and inside it.
+pub(crate) fn normalize_filename_template(template: &str) -> Result<String, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("Filename template is empty".to_string());
+    }
+    let path = std::path::Path::new(template);
+    if path.is_absolute()
+        || template.starts_with(['/', '\\'])
+        || path.components().any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err("Filename template must be a relative path inside the download folder".to_string());
+    }
+
+    if template.contains("%(ext)s") {
+        Ok(template.to_string())
+    } else {
+        Ok(format!("{}.%(ext)s", template))
+    }
+}
+
+/// Which subtitles a download fetches and what happens to them
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleConfig {
+    /// Write subtitles as files next to the download
+    pub write: bool,
+    /// Also fetch auto-generated captions for languages without real subtitles
+    pub auto_generated: bool,
+    /// Embed subtitles into the video file (video formats only)
+    pub embed: bool,
+    /// Language codes such as `en` or `de` (yt-dlp's default when empty)
+    pub languages: Vec<String>,
+    /// Convert subtitles to this format (`srt`, `vtt`, `ass` or `lrc`; `srt` for audio when unset)
+    pub format: Option<String>,
+    /// Keep the originally downloaded subtitles next to the converted ones
+    pub keep_original: bool,
+}
+
+impl Default for SubtitleConfig {
+    fn default() -> Self {
+        Self {
+            write: true,
+            auto_generated: false,
+            embed: false,
+            languages: Vec::new(),
+            format: None,
+            keep_original: false,
+        }
+    }
+}
+
+impl SubtitleConfig {
+    /// Reject settings yt-dlp would misread or refuse
+    fn validate(&self, format: DownloadFormat) -> Result<(), DownloadError> {
+        if let Some(sub_format) = &self.format {
+            if !SUBTITLE_FORMATS.contains(&sub_format.as_str()) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Unsupported subtitle format: {}",
+                    sub_format
+                )));
+            }
+        }
+        // `--sub-langs` is comma-separated, so one entry can't hold a comma
+        if let Some(lang) = self
+            .languages
+            .iter()
+            .find(|lang| lang.trim().is_empty() || lang.contains(','))
+        {
+            return Err(DownloadError::InvalidOption(format!(
+                "Invalid subtitle language \"{}\"",
+                lang
+            )));
+        }
+        if self.embed && !format.is_video() {
+            return Err(DownloadError::InvalidOption(
+                "Subtitles can only be embedded in video downloads".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Value for `--sub-langs`, or `None` for yt-dlp's default languages
+    fn sub_langs(&self) -> Option<String> {
+        (!self.languages.is_empty()).then(|| {
+            self.languages
+                .iter()
+                .map(|lang| lang.trim())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+    }
+}
+
+/// Per-download options; every field defaults to the standard behavior
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadOptions {
+    /// Netscape-format cookies file for sites that need a signed-in session
+    pub cookies_file: Option<String>,
+    /// Mark the video as watched on the site (only effective with cookies)
+    pub mark_watched: bool,
+    /// PEM client certificate for servers or proxies that require mutual TLS
+    pub client_certificate: Option<String>,
+    /// Private key for `client_certificate`, when not bundled in the same file
+    pub client_certificate_key: Option<String>,
+    /// Prefer the largest stream that fits this budget instead of aborting
+    pub max_size_mb: Option<u64>,
+    /// Sort candidate streams by audio bitrate so the highest one is extracted
+    pub prefer_audio_bitrate: bool,
+    /// Encoding quality of extracted MP3 audio (FLAC is always lossless)
+    pub audio_quality: AudioQuality,
+    /// Highest video resolution to download, e.g. 1080 (best available when unset)
+    pub max_height: Option<u32>,
+    /// How the output file is named (a saved custom template replaces the default)
+    pub filename_scheme: FilenameScheme,
+    /// Sort into `Artist/Album/Track` folders (overrides the naming scheme)
+    pub library_mode: bool,
+    /// Include this item in an M3U playlist written when its batch finishes
+    pub write_playlist_file: bool,
+    /// Download every entry of a playlist URL instead of only the linked video
+    pub playlist: bool,
+    /// 1-based playlist entry to start from (used to resume interrupted playlists)
+    pub playlist_start: Option<u32>,
+    /// Subtitles to fetch (none when unset)
+    pub subtitles: Option<SubtitleConfig>,
+    /// Save each item's cover art as a `.jpg` with the same base name
+    ///
+    /// Media managers pick these up as album art for music libraries.
+    pub write_thumbnail: bool,
+    /// Write title, artist, album and track tags into the output file
+    pub embed_metadata: bool,
+    /// Embed the thumbnail in the output file: cover art for audio and MP4,
+    /// an attachment for MKV; skipped with a warning for WebM
+    pub embed_thumbnail: bool,
+    /// Write straight to the final file instead of a `.part` file
+    ///
+    /// Works around shares (SMB/NFS) that fail the `.part` rename, at the cost
+    /// that a crash leaves an incomplete file under the final name. Staged
+    /// files are still checked before they leave the staging dir.
+    pub no_part: bool,
+    /// Resample extracted audio to this rate in Hz (source rate when unset)
+    pub audio_sample_rate: Option<u32>,
+    /// Downmix extracted audio to 1 (mono) or 2 (stereo) channels (source layout when unset)
+    pub audio_channels: Option<u8>,
+    /// Compare the output's duration with the metadata (costs an ffprobe run)
+    pub verify_duration: bool,
+    /// Decode the output and reject it when the audio is silent or corrupt
+    /// (costs a full ffmpeg decode)
+    pub verify_audio: bool,
+    /// Only download items uploaded on or after this date
+    pub date_after: Option<String>,
+    /// Only download items uploaded on or before this date
+    pub date_before: Option<String>,
+    /// Extractor arguments, e.g. `youtube:player_client=web`
+    pub extractor_args: Option<String>,
+    /// Pace this download with a preset instead of the saved anti-ban settings
+    pub anti_ban_profile: Option<crate::anti_ban::AntiBanProfile>,
+    /// Cap this download's speed, e.g. `500K` or `2M` (global anti-ban cap when unset)
+    pub rate_limit: Option<String>,
+    /// Only download this `(start, end)` section, as `SS`, `MM:SS` or `HH:MM:SS`
+    pub clip: Option<(String, String)>,
+    /// SponsorBlock categories to cut from this download (saved config when empty)
+    pub sponsorblock_remove: Vec<String>,
+}
+
+impl DownloadOptions {
+    /// Reject options that would make yt-dlp fail after spawning
+    fn validate(&self, format: DownloadFormat) -> Result<(), DownloadError> {
+        if let Some(cookies) = &self.cookies_file {
+            if !std::path::Path::new(cookies).is_file() {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Cookies file not found: {}",
+                    cookies
+                )));
+            }
+        }
+        self.validate_client_certificate()?;
+        if let Some(subtitles) = &self.subtitles {
+            subtitles.validate(format)?;
+        }
+        for date in [&self.date_after, &self.date_before].into_iter().flatten() {
+            if !DATE_FILTER_REGEX.is_match(date) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Invalid date {} (use YYYYMMDD or e.g. today-7days)",
+                    date
+                )));
+            }
+        }
+        if let Some(rate) = self.audio_sample_rate {
+            if format.is_video() {
+                return Err(DownloadError::InvalidOption(
+                    "Sample rate only applies to audio downloads".to_string(),
+                ));
+            }
+            if !SAMPLE_RATES.contains(&rate) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Unsupported sample rate: {} Hz",
+                    rate
+                )));
+            }
+            if format == DownloadFormat::Mp3 && rate > MP3_MAX_SAMPLE_RATE {
+                return Err(DownloadError::InvalidOption(format!(
+                    "MP3 supports at most {} Hz",
+                    MP3_MAX_SAMPLE_RATE
+                )));
+            }
+        }
+        if let Some(channels) = self.audio_channels {
+            if format.is_video() {
+                return Err(DownloadError::InvalidOption(
+                    "Channel count only applies to audio downloads".to_string(),
+                ));
+            }
+            if !matches!(channels, 1 | 2) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Unsupported channel count: {} (use 1 for mono or 2 for stereo)",
+                    channels
+                )));
+            }
+        }
+        self.clip_range()?;
+        if let Some(rate) = &self.rate_limit {
+            crate::anti_ban::validate_rate(rate).map_err(DownloadError::InvalidOption)?;
+        }
+        crate::sponsorblock::validate_categories(&self.sponsorblock_remove)
+            .map_err(DownloadError::InvalidOption)?;
+        if let Some(start) = self.playlist_start {
+            if start == 0 || !self.playlist {
+                return Err(DownloadError::InvalidOption(
+                    "Playlist start must be a 1-based index on a playlist download".to_string(),
+                ));
+            }
+        }
+        if self.max_height == Some(0) {
+            return Err(DownloadError::InvalidOption(
+                "Resolution cap must be greater than zero".to_string(),
+            ));
+        }
+        if self.max_size_mb == Some(0) {
+            return Err(DownloadError::InvalidOption(
+                "Size cap must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Start and end of `clip` in seconds, if set
+    fn clip_range(&self) -> Result<Option<(f64, f64)>, DownloadError> {
+        let Some((start, end)) = &self.clip else {
+            return Ok(None);
+        };
+        let parse = |value: &str| {
+            crate::commands::media::parse_timestamp(value).ok_or_else(|| {
+                DownloadError::InvalidOption(format!(
+                    "Invalid clip time \"{}\" (use seconds or HH:MM:SS)",
+                    value
+                ))
+            })
+        };
+        let (start_secs, end_secs) = (parse(start)?, parse(end)?);
+        if start_secs >= end_secs {
+            return Err(DownloadError::InvalidOption(
+                "Clip start must be before its end".to_string(),
+            ));
+        }
+        Ok(Some((start_secs, end_secs)))
+    }
+
+    /// Check the client certificate (and key) are readable PEM files
+    fn validate_client_certificate(&self) -> Result<(), DownloadError> {
+        let read_pem = |path: &str, what: &str| {
+            std::fs::read_to_string(path).map_err(|e| {
+                DownloadError::InvalidOption(format!("Cannot read {} {}: {}", what, path, e))
+            })
+        };
+
+        let Some(cert_path) = &self.client_certificate else {
+            if self.client_certificate_key.is_some() {
+                return Err(DownloadError::InvalidOption(
+                    "Client certificate key set without a certificate".to_string(),
+                ));
+            }
+            return Ok(());
+        };
+
+        let cert = read_pem(cert_path, "client certificate")?;
+        if !cert.contains("-----BEGIN CERTIFICATE-----") {
+            return Err(DownloadError::InvalidOption(format!(
+                "Client certificate is not a PEM certificate: {}",
+                cert_path
+            )));
+        }
+
+        // The key may live in the certificate file itself
+        let (key, key_path) = match &self.client_certificate_key {
+            Some(key_path) => (read_pem(key_path, "client certificate key")?, key_path),
+            None => (cert, cert_path),
+        };
+        if !PEM_PRIVATE_KEY_REGEX.is_match(&key) {
+            return Err(DownloadError::InvalidOption(format!(
+                "No PEM private key found in {}",
+                key_path
+            )));
+        }
+        Ok(())
+    }
+
+    /// ffmpeg arguments for yt-dlp's ExtractAudio postprocessor, if any
+    fn extract_audio_args(&self) -> Option<String> {
+        let mut ffmpeg_args = Vec::new();
+        if let Some(rate) = self.audio_sample_rate {
+            ffmpeg_args.push(format!("-ar {}", rate));
+        }
+        if let Some(channels) = self.audio_channels {
+            ffmpeg_args.push(format!("-ac {}", channels));
+        }
+
+        if ffmpeg_args.is_empty() {
+            None
+        } else {
+            Some(format!("ExtractAudio:{}", ffmpeg_args.join(" ")))
+        }
+    }
+
+    /// Format selector picking the best stream under the size cap
+    ///
+    /// `<?` also accepts formats that report no size, and the trailing
+    /// alternatives fall back to the best stream when nothing matches.
+    /// Video downloads always get a selector so separate video and audio
+    /// streams are merged, capped at `max_height` when set. A capped selector
+    /// ends in an uncapped alternative so a video with nothing at or below the
+    /// cap still downloads (the `res` sort then picks the closest height).
+    fn format_selector(&self, format: DownloadFormat) -> Option<String> {
+        let height = self
+            .max_height
+            .map(|h| format!("[height<={}]", h))
+            .unwrap_or_default();
+        let fallback = if self.max_height.is_some() {
+            "/bestvideo*+bestaudio/best"
+        } else {
+            ""
+        };
+        let selector = match (format.is_video(), self.max_size_mb) {
+            (false, Some(mb)) => Some(format!(
+                "bestaudio[filesize<?{mb}M]/best[filesize<?{mb}M]/bestaudio/best",
+                mb = mb
+            )),
+            (false, None) => None,
+            (true, Some(mb)) => Some(format!(
+                "best{h}[filesize<?{mb}M]/bestvideo*{h}[filesize<?{mb}M]+bestaudio/bestvideo*{h}+bestaudio/best{h}",
+                h = height,
+                mb = mb
+            )),
+            (true, None) => Some(format!("bestvideo*{h}+bestaudio/best{h}", h = height)),
+        };
+        selector.map(|s| if format.is_video() { s + fallback } else { s })
+    }
+}
+
+/// Per-call download options exactly as the caller sent them
+///
+/// Only the fields present override a site profile, so an explicit `false`
+/// or default value still beats the profile while an omitted one doesn't.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OptionOverrides(serde_json::Map<String, serde_json::Value>);
+
+impl OptionOverrides {
+    /// Whether the caller set `field`
+    pub fn contains(&self, field: &str) -> bool {
+        self.0.contains_key(field)
+    }
+
+    /// Layer the fields that were set over `defaults`
+    pub fn merged_over(&self, defaults: &DownloadOptions) -> Result<DownloadOptions, DownloadError> {
+        let mut merged = match serde_json::to_value(defaults) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        merged.extend(self.0.clone());
+        serde_json::from_value(serde_json::Value::Object(merged))
+            .map_err(|e| DownloadError::InvalidOption(format!("Invalid download options: {}", e)))
+    }
+
+    /// The options with no profile applied
+    pub fn to_options(&self) -> Result<DownloadOptions, DownloadError> {
+        self.merged_over(&DownloadOptions::default())
+    }
+
+    /// Overrides that set every field to the value in `options`
+    pub fn from_options(options: &DownloadOptions) -> Self {
+        match serde_json::to_value(options) {
+            Ok(serde_json::Value::Object(map)) => Self(map),
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Download result returned to frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DownloadResult {
     pub title: String,
     pub artist: Option<String>,
@@ -57,6 +670,24 @@ pub struct DownloadResult {
     pub thumbnail_path: Option<String>,
     #[serde(rename = "outputPath")]
     pub output_path: String,
+    /// Achieved transfer speed, measured from the progress stream
+    #[serde(rename = "averageSpeedBytesPerSec", default)]
+    pub average_speed_bytes_per_sec: Option<f64>,
+    /// Subtitle files written alongside the audio
+    #[serde(rename = "subtitlePaths", default)]
+    pub subtitle_paths: Vec<String>,
+    /// Items skipped because their upload date was outside the date range
+    #[serde(rename = "filteredByDate", default)]
+    pub filtered_by_date: u32,
+    /// Every file of a playlist download, in download order
+    #[serde(rename = "playlistItems", default)]
+    pub playlist_items: Vec<DownloadResult>,
+    /// Errors for playlist entries that failed while the rest continued
+    #[serde(rename = "failedItems", default)]
+    pub failed_items: Vec<String>,
+    /// Nothing was written because the download archive already listed it
+    #[serde(default)]
+    pub skipped: bool,
 }
 
 /// Download error types
@@ -73,6 +704,62 @@ pub enum DownloadError {
 
     #[error("Safety gate locked")]
     GateLocked,
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Incomplete download: {0}")]
+    IncompleteDownload(String),
+
+    #[error("Corrupt output: {0}")]
+    CorruptOutput(String),
+
+    #[error("Network activity is stopped")]
+    NetworkStopped,
+
+    #[error("Download cancelled")]
+    Cancelled,
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Geo-blocked: {0}")]
+    GeoBlocked(String),
+
+    #[error("Sign-in required: {0}")]
+    AuthRequired(String),
+
+    #[error("Video unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    #[error("This URL failed recently, try again in {retry_after_secs}s")]
+    Cooldown { retry_after_secs: u64 },
+}
+
+impl DownloadError {
+    /// Stable category name used to group recent errors
+    pub fn category(&self) -> &'static str {
+        match self {
+            DownloadError::InvalidUrl(_) => "invalid_url",
+            DownloadError::SidecarError(_) => "sidecar_error",
+            DownloadError::DownloadFailed(_) => "download_failed",
+            DownloadError::GateLocked => "gate_locked",
+            DownloadError::InvalidOption(_) => "invalid_option",
+            DownloadError::IncompleteDownload(_) => "incomplete_download",
+            DownloadError::CorruptOutput(_) => "corrupt_output",
+            DownloadError::NetworkStopped => "network_stopped",
+            DownloadError::Cancelled => "cancelled",
+            DownloadError::RateLimited(_) => "rate_limited",
+            DownloadError::GeoBlocked(_) => "geo_blocked",
+            DownloadError::AuthRequired(_) => "auth_required",
+            DownloadError::Unavailable(_) => "unavailable",
+            DownloadError::NetworkError(_) => "network_error",
+            DownloadError::Cooldown { .. } => "cooldown",
+        }
+    }
 }
 
 impl Serialize for DownloadError {
@@ -84,22 +771,182 @@ impl Serialize for DownloadError {
     }
 }
 
+/// Markers in yt-dlp stderr that identify a failure category
+const RATE_LIMIT_MARKERS: &[&str] = &[
+    "http error 429",
+    "too many requests",
+    "confirm you're not a bot",
+    "confirm you’re not a bot",
+    "rate-limited",
+    "rate limit",
+];
+const GEO_BLOCK_MARKERS: &[&str] = &[
+    "not available in your country",
+    "geo restriction",
+    "geo-restricted",
+    "blocked it in your country",
+];
+const AUTH_MARKERS: &[&str] = &[
+    "sign in to confirm your age",
+    "private video",
+    "members-only",
+    "join this channel",
+    "login required",
+    "requires authentication",
+    "use --cookies",
+];
+const NETWORK_MARKERS: &[&str] = &[
+    "timed out",
+    "connection reset",
+    "connection refused",
+    "connection aborted",
+    "remote end closed connection",
+    "temporary failure in name resolution",
+    "name or service not known",
+    "network is unreachable",
+    "incompleteread",
+    "unable to download video data",
+    "http error 500",
+    "http error 502",
+    "http error 503",
+    "http error 504",
+];
+const UNAVAILABLE_MARKERS: &[&str] = &[
+    "video unavailable",
+    "has been removed",
+    "account associated with this video has been terminated",
+    "unsupported url",
+    "is not a valid url",
+];
+
+/// Classify yt-dlp stderr into a specific `DownloadError`
+pub(crate) fn classify_failure(stderr: &str) -> DownloadError {
+    // Prefer the last "ERROR:" line, otherwise the last non-empty line
+    let message = stderr
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("ERROR:").map(|m| m.trim().to_string()))
+        .or_else(|| {
+            stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| line.to_string())
+        })
+        .unwrap_or_else(|| "Download failed".to_string());
+
+    let lower = stderr.to_lowercase();
+    let matches = |markers: &[&str]| markers.iter().any(|m| lower.contains(m));
+
+    if matches(RATE_LIMIT_MARKERS) {
+        DownloadError::RateLimited(message)
+    } else if matches(GEO_BLOCK_MARKERS) {
+        DownloadError::GeoBlocked(message)
+    } else if matches(AUTH_MARKERS) {
+        DownloadError::AuthRequired(message)
+    } else if matches(UNAVAILABLE_MARKERS) {
+        DownloadError::Unavailable(message)
+    } else if matches(NETWORK_MARKERS) {
+        DownloadError::NetworkError(message)
+    } else {
+        DownloadError::DownloadFailed(message)
+    }
+}
+
 /// Progress event payload
 #[derive(Clone, Serialize)]
 struct ProgressPayload {
+    #[serde(rename = "downloadId")]
+    download_id: String,
     progress: f64,
     status: String,
+    /// Current speed while downloading, the average once complete
+    #[serde(rename = "speedBytesPerSec")]
+    speed_bytes_per_sec: Option<f64>,
+    /// Time remaining as estimated by yt-dlp
+    #[serde(rename = "etaSecs")]
+    eta_secs: Option<u64>,
+    /// Speed for display, as yt-dlp prints it (e.g. `2.50MiB/s`)
+    speed: Option<String>,
+    /// Time remaining for display, as yt-dlp prints it (e.g. `00:42`)
+    eta: Option<String>,
+    /// 1-based playlist entry the progress belongs to (playlist downloads only)
+    #[serde(rename = "playlistIndex")]
+    playlist_index: Option<u32>,
+}
+
+/// Measures achieved throughput from the cumulative byte counts on progress lines
+#[derive(Default)]
+struct ThroughputTracker {
+    started_at: Option<std::time::Instant>,
+    last_at: Option<std::time::Instant>,
+    last_bytes: Option<f64>,
+    transferred: f64,
+}
+
+impl ThroughputTracker {
+    /// Record the downloaded byte count reported by a progress line
+    fn record(&mut self, downloaded_bytes: f64) {
+        let now = std::time::Instant::now();
+        match self.last_bytes {
+            // The first reading is the starting point (non-zero when resuming)
+            None => self.started_at = Some(now),
+            Some(last) if downloaded_bytes >= last => self.transferred += downloaded_bytes - last,
+            // Counter went backwards: a new file (e.g. a separate stream) started
+            Some(_) => self.transferred += downloaded_bytes,
+        }
+        self.last_bytes = Some(downloaded_bytes);
+        self.last_at = Some(now);
+    }
+
+    /// Average bytes per second over the observed transfer
+    fn average_bytes_per_sec(&self) -> Option<f64> {
+        let elapsed = self.last_at?.duration_since(self.started_at?).as_secs_f64();
+        if elapsed <= 0.0 || self.transferred <= 0.0 {
+            return None;
+        }
+        Some(self.transferred / elapsed)
+    }
+}
+
+/// Resume event payload, sent once the total size is known
+#[derive(Clone, Serialize)]
+struct ResumePayload {
+    #[serde(rename = "resumeBytes")]
+    resume_bytes: u64,
+    #[serde(rename = "totalBytes")]
+    total_bytes: Option<u64>,
+    progress: Option<f64>,
+}
+
+/// Payload identifying a download in lifecycle events
+#[derive(Clone, Serialize)]
+struct DownloadIdPayload {
+    #[serde(rename = "downloadId")]
+    download_id: String,
+    url: String,
+}
+
+/// Playlist progress event payload, sent as each entry starts
+#[derive(Clone, Serialize)]
+struct PlaylistProgressPayload {
+    #[serde(rename = "downloadId")]
+    download_id: String,
+    index: u32,
+    total: u32,
+    current_title: Option<String>,
 }
 
 /// Log event payload
 #[derive(Clone, Serialize)]
-struct LogPayload {
-    level: String,
-    message: String,
+pub(crate) struct LogPayload {
+    pub level: String,
+    pub message: String,
 }
 
 /// Validate URL format
-fn validate_url(url: &str) -> Result<(), DownloadError> {
+pub(crate) fn validate_url(url: &str) -> Result<(), DownloadError> {
     // Basic URL validation - yt-dlp handles the rest
     if url.is_empty() {
         return Err(DownloadError::InvalidUrl("URL cannot be empty".to_string()));
@@ -114,8 +961,15 @@ fn validate_url(url: &str) -> Result<(), DownloadError> {
     Ok(())
 }
 
+/// Payload for `download-path-unavailable` events
+#[derive(Clone, Serialize)]
+struct PathUnavailablePayload {
+    path: String,
+    kind: crate::storage::VolumeKind,
+}
+
 /// Get download directory
-fn get_download_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> PathBuf {
+pub(crate) fn get_download_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> PathBuf {
     use tauri_plugin_store::StoreExt;
     
     // 1. Try to get custom path from store
@@ -126,6 +980,27 @@ fn get_download_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> PathBuf {
                 if path.exists() {
                     return path;
                 }
+
+                // Usually an unplugged drive or unmounted share - tell the UI
+                // instead of silently writing somewhere else
+                let kind = crate::storage::volume_kind(&path);
+                let _ = app.emit(
+                    "download-path-unavailable",
+                    PathUnavailablePayload {
+                        path: path.to_string_lossy().to_string(),
+                        kind,
+                    },
+                );
+                let _ = app.emit(
+                    "download-log",
+                    LogPayload {
+                        level: "warn".to_string(),
+                        message: format!(
+                            "Download folder {} is unavailable; using the default downloads folder",
+                            path.to_string_lossy()
+                        ),
+                    },
+                );
             }
         }
     }
@@ -153,8 +1028,6 @@ pub fn get_download_path(app: tauri::AppHandle) -> Result<String, String> {
 /// Set download path in store
 #[tauri::command]
 pub fn set_download_path(path: String, app: tauri::AppHandle) -> Result<(), String> {
-    use tauri_plugin_store::StoreExt;
-    
     // Validate that the path exists and is a directory
     let p = PathBuf::from(&path);
     if !p.exists() {
@@ -164,51 +1037,1015 @@ pub fn set_download_path(path: String, app: tauri::AppHandle) -> Result<(), Stri
         return Err("Path is not a directory".to_string());
     }
 
-    let store = app.store("settings.bin").map_err(|e| format!("Failed to open store: {}", e))?;
-    
-    store.set("downloadPath", serde_json::json!(path));
-    store.save().map_err(|e| e.to_string())?;
+    crate::settings::write_store(
+        &app,
+        crate::settings::STORE_PATH,
+        "downloadPath",
+        serde_json::json!(path),
+    )?;
+
+    // Detachable volumes fall back to the default folder while disconnected
+    if crate::storage::volume_kind(&p).is_detachable() {
+        let _ = app.emit(
+            "download-log",
+            LogPayload {
+                level: "warn".to_string(),
+                message: format!(
+                    "{} is on a removable or network drive; downloads fall back to the default folder while it is disconnected",
+                    path
+                ),
+            },
+        );
+    }
     Ok(())
 }
 
+/// A named download folder the user can switch to
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadPathPreset {
+    pub name: String,
+    pub path: String,
+    /// Whether this preset's path is the current download path
+    pub active: bool,
+}
+
+/// Load the saved download path presets (name -> path)
+fn load_path_presets<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> std::collections::BTreeMap<String, String> {
+    use tauri_plugin_store::StoreExt;
+
+    app.store("settings.bin")
+        .ok()
+        .and_then(|store| store.get("downloadPathPresets"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Save a named download path preset, replacing any preset with the same name
+#[tauri::command]
+pub async fn save_download_path_preset(name: String, path: String, app: tauri::AppHandle) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+
+    // Validate that the path exists and is a directory
+    let p = PathBuf::from(&path);
+    if !p.exists() {
+        return Err("Directory does not exist".to_string());
+    }
+    if !p.is_dir() {
+        return Err("Path is not a directory".to_string());
+    }
+
+    crate::settings::update_store(
+        &app,
+        crate::settings::STORE_PATH,
+        "downloadPathPresets",
+        |presets: &mut std::collections::BTreeMap<String, String>| {
+            presets.insert(name, path);
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// List saved download path presets, sorted by name
+#[tauri::command]
+pub fn list_download_path_presets(app: tauri::AppHandle) -> Result<Vec<DownloadPathPreset>, String> {
+    let active = get_download_path(app.clone())?;
+
+    Ok(load_path_presets(&app)
+        .into_iter()
+        .map(|(name, path)| DownloadPathPreset {
+            active: path == active,
+            name,
+            path,
+        })
+        .collect())
+}
+
+/// Make a preset's path the active download path
+#[tauri::command]
+pub fn activate_download_path_preset(name: String, app: tauri::AppHandle) -> Result<(), String> {
+    let path = load_path_presets(&app)
+        .remove(&name)
+        .ok_or_else(|| format!("Unknown preset: {}", name))?;
+
+    set_download_path(path, app)
+}
+
+/// Parse a server-requested wait (seconds) from a `Retry-After` in yt-dlp's output
+fn parse_retry_after(output: &str) -> Option<u64> {
+    RETRY_AFTER_REGEX
+        .captures_iter(output)
+        .last()
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+/// Values parsed from one yt-dlp progress line
+///
+/// Speed and ETA are None while yt-dlp still prints `Unknown` or `--:--`.
+struct ProgressInfo {
+    progress: f64,
+    speed_bytes_per_sec: Option<f64>,
+    eta_secs: Option<u64>,
+    speed: Option<String>,
+    eta: Option<String>,
+}
+
 /// Parse progress from yt-dlp output (uses cached regex for performance)
-fn parse_progress(line: &str) -> Option<f64> {
-    // Match patterns like "[download]  45.2% of 10.24MiB"
-    PROGRESS_REGEX
+fn parse_progress(line: &str) -> Option<ProgressInfo> {
+    // Match patterns like "[download]  45.2% of 10.24MiB at 2.15MiB/s ETA 00:45"
+    let progress = PROGRESS_REGEX
+        .captures(line)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<f64>().ok())?;
+
+    let speed_caps = SPEED_REGEX.captures(line);
+    let speed_bytes_per_sec = speed_caps.as_ref().and_then(|caps| {
+        let value = caps.get(1)?.as_str().parse::<f64>().ok()?;
+        Some(value * unit_multiplier(caps.get(2)?.as_str())?)
+    });
+    let speed = speed_caps
+        .as_ref()
+        .and_then(|caps| Some(format!("{}{}/s", caps.get(1)?.as_str(), caps.get(2)?.as_str())));
+
+    let eta = ETA_REGEX
+        .captures(line)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string());
+    let eta_secs = eta.as_deref().and_then(|eta| {
+        eta.split(':')
+            .try_fold(0u64, |secs, part| Some(secs * 60 + part.parse::<u64>().ok()?))
+    });
+
+    Some(ProgressInfo {
+        progress,
+        speed_bytes_per_sec,
+        eta_secs,
+        speed,
+        eta,
+    })
+}
+
+/// Format a byte rate the way yt-dlp prints it
+fn format_speed(bytes_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes_per_sec;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2}{}/s", value, UNITS[unit])
+}
+
+/// Bytes per unit for the size suffixes yt-dlp prints
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    Some(match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    })
+}
+
+/// Parse the total size in bytes from a progress line
+fn parse_total_size(line: &str) -> Option<u64> {
+    let caps = SIZE_REGEX.captures(line)?;
+    let value = caps.get(1)?.as_str().parse::<f64>().ok()?;
+    Some((value * unit_multiplier(caps.get(2)?.as_str())?) as u64)
+}
+
+/// Parse the byte offset from a "Resuming download at byte N" line
+fn parse_resume_offset(line: &str) -> Option<u64> {
+    RESUME_REGEX
         .captures(line)
         .and_then(|caps| caps.get(1))
-        .and_then(|m| m.as_str().parse::<f64>().ok())
+        .and_then(|m| m.as_str().parse::<u64>().ok())
+}
+
+/// Parse (index, total) from a "Downloading item N of M" line
+fn parse_playlist_item(line: &str) -> Option<(u32, u32)> {
+    let caps = PLAYLIST_ITEM_REGEX.captures(line)?;
+    Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+}
+
+/// Parse the chosen format ids from yt-dlp's "[info] ...: Downloading N format(s): ..." line
+fn parse_selected_format(line: &str) -> Option<String> {
+    SELECTED_FORMAT_REGEX.captures(line).map(|caps| caps[1].to_string())
+}
+
+/// Parse the file stem from a "[download] Destination: ..." line
+fn parse_destination_title(line: &str) -> Option<String> {
+    let path = line.split("[download] Destination:").nth(1)?.trim();
+    std::path::Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+/// Generate a short unique id for a download or queued job
+pub(crate) fn new_download_id() -> String {
+    format!(
+        "{:x}{:04x}",
+        chrono::Local::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+/// Settings `build_download_args` reads from the app, loaded once up front
+#[cfg(not(target_os = "android"))]
+#[derive(Default)]
+struct ArgSettings {
+    /// Continue partial files left by an earlier attempt
+    resume: bool,
+    anti_ban: crate::anti_ban::AntiBanConfig,
+    /// Custom header names for the log, with their arguments
+    headers: Option<(String, Vec<String>)>,
+    sponsorblock: crate::sponsorblock::SponsorBlockConfig,
+    archive_args: Vec<String>,
+    /// Bundled ffmpeg, when installed
+    ffmpeg_path: Option<PathBuf>,
+}
+
+#[cfg(not(target_os = "android"))]
+impl ArgSettings {
+    fn load(app: &tauri::AppHandle, options: &DownloadOptions) -> Self {
+        Self {
+            resume: crate::settings::get_bool(app, crate::settings::RESUME_DOWNLOADS, true),
+            anti_ban: crate::anti_ban::effective_config(app, options.anti_ban_profile),
+            headers: crate::headers::describe(app)
+                .map(|names| (names, crate::headers::to_ytdlp_args(app))),
+            sponsorblock: crate::sponsorblock::effective_config(app, &options.sponsorblock_remove),
+            archive_args: crate::archive::to_ytdlp_args(app),
+            ffmpeg_path: get_sidecar_path(app, SidecarType::Ffmpeg).ok(),
+        }
+    }
+}
+
+/// Build the yt-dlp argument list for a download, URL last
+///
+/// Notes about the chosen settings go to `log` instead of being emitted, so
+/// a dry run can build the same arguments without touching the download log.
+#[cfg(not(target_os = "android"))]
+pub(crate) fn build_download_args(
+    app: &tauri::AppHandle,
+    url: &str,
+    format: DownloadFormat,
+    options: &DownloadOptions,
+    proxy_config: &proxy::ProxyConfig,
+    output_template: &str,
+    log: &mut Vec<LogPayload>,
+) -> Vec<String> {
+    build_args_with(
+        &ArgSettings::load(app, options),
+        url,
+        format,
+        options,
+        proxy_config,
+        output_template,
+        log,
+    )
+}
+
+/// `build_download_args` with the app settings already loaded
+#[cfg(not(target_os = "android"))]
+fn build_args_with(
+    settings: &ArgSettings,
+    url: &str,
+    format: DownloadFormat,
+    options: &DownloadOptions,
+    proxy_config: &proxy::ProxyConfig,
+    output_template: &str,
+    log: &mut Vec<LogPayload>,
+) -> Vec<String> {
+    // Build command arguments
+    let mut args: Vec<String> = vec![
+        "--output".to_string(),
+        output_template.to_string(),
+        "--newline".to_string(),      // Progress on new lines
+        "--no-colors".to_string(),    // Clean output for parsing
+    ];
+
+    // Resume partial files left by a failed or interrupted attempt
+    args.push(if settings.resume { "--continue" } else { "--no-continue" }.to_string());
+
+    if options.playlist {
+        // Keep going past entries that fail instead of aborting the run
+        args.push("--yes-playlist".to_string());
+        args.push("--ignore-errors".to_string());
+        if let Some(start) = options.playlist_start {
+            args.push("--playlist-start".to_string());
+            args.push(start.to_string());
+        }
+    } else {
+        args.push("--no-playlist".to_string()); // Single video only
+    }
+
+    match format.audio() {
+        Some(audio) => {
+            args.push("--extract-audio".to_string());
+            args.push("--audio-format".to_string());
+            args.push(audio.as_str().to_string());
+
+            // Add quality arguments
+            for arg in audio.quality_args(options.audio_quality) {
+                args.push(arg.to_string());
+            }
+            log.push(LogPayload {
+                level: "info".to_string(),
+                message: match audio {
+                    AudioFormat::Mp3 => format!("Audio quality: {}", options.audio_quality.label()),
+                    AudioFormat::Flac => "Audio quality: lossless".to_string(),
+                },
+            });
+        }
+        None => {
+            // Merged streams land in the requested container; a single
+            // pre-muxed stream is remuxed so the extension always matches
+            args.push("--merge-output-format".to_string());
+            args.push(format.as_str().to_string());
+            args.push("--remux-video".to_string());
+            args.push(format.as_str().to_string());
+
+            // Largest height up to the cap, else the smallest one above it
+            if let Some(h) = options.max_height {
+                args.push("--format-sort".to_string());
+                args.push(format!("res:{}", h));
+            }
+
+            log.push(LogPayload {
+                level: "info".to_string(),
+                message: match options.max_height {
+                    Some(h) => format!("Video quality: up to {}p (closest available otherwise)", h),
+                    None => "Video quality: best available".to_string(),
+                },
+            });
+        }
+    }
+
+    // Pick a stream that fits the size budget
+    if let Some(selector) = options.format_selector(format) {
+        args.push("--format".to_string());
+        args.push(selector);
+    }
+
+    // Prefer the highest audio bitrate over yt-dlp's default ordering
+    if options.prefer_audio_bitrate && !format.is_video() {
+        args.push("--format-sort".to_string());
+        args.push("abr".to_string());
+    }
+
+    // Add proxy arguments
+    args.extend(proxy_config.to_ytdlp_args());
+
+    // Add User-Agent arguments
+    let anti_ban_config = &settings.anti_ban;
+    if anti_ban_config.rotate_user_agent {
+        args.extend(anti_ban_config.to_ytdlp_args());
+        log.push(LogPayload {
+            level: "info".to_string(),
+            message: "Using rotated User-Agent".to_string(),
+        });
+    }
+
+    // User-managed headers (names only in the log)
+    if let Some((names, header_args)) = &settings.headers {
+        args.extend(header_args.iter().cloned());
+        log.push(LogPayload {
+            level: "info".to_string(),
+            message: format!("Custom headers: {}", names),
+        });
+    }
+
+    // Cut SponsorBlock segments (YouTube only; ignored elsewhere)
+    let sponsorblock = &settings.sponsorblock;
+    if sponsorblock.is_active() {
+        args.extend(sponsorblock.to_ytdlp_args());
+        log.push(LogPayload {
+            level: "info".to_string(),
+            message: format!("SponsorBlock: removing {}", sponsorblock.categories.join(", ")),
+        });
+    }
+
+    // Add rate limiting arguments (a per-download cap replaces the global one)
+    let rate_args = match &options.rate_limit {
+        Some(rate) => crate::anti_ban::AntiBanConfig {
+            limit_rate: Some(rate.clone()),
+            ..anti_ban_config.clone()
+        }
+        .rate_args(),
+        None => anti_ban_config.rate_args(),
+    };
+    if !rate_args.is_empty() {
+        log.push(LogPayload {
+            level: "info".to_string(),
+            message: format!("Rate limiting: {}", rate_args.join(" ")),
+        });
+        args.extend(rate_args);
+    }
+
+    // Pass extra ffmpeg arguments to the audio extraction step
+    if let Some(ppa) = options.extract_audio_args() {
+        args.push("--postprocessor-args".to_string());
+        args.push(ppa);
+    }
+
+    // Only fetch the requested section; cutting on keyframes that aren't
+    // there leaves silence or frozen video at the start of the clip
+    if let Some((start, end)) = &options.clip {
+        args.push("--download-sections".to_string());
+        args.push(format!("*{}-{}", start.trim(), end.trim()));
+        args.push("--force-keyframes-at-cuts".to_string());
+    }
+
+    // Restrict by upload date
+    if let Some(date) = &options.date_after {
+        args.push("--dateafter".to_string());
+        args.push(date.clone());
+    }
+    if let Some(date) = &options.date_before {
+        args.push("--datebefore".to_string());
+        args.push(date.clone());
+    }
+
+    // Site-specific extractor behavior
+    if let Some(extractor_args) = &options.extractor_args {
+        args.push("--extractor-args".to_string());
+        args.push(extractor_args.clone());
+    }
+
+    // Skip the .part file on filesystems that can't rename it
+    if options.no_part {
+        args.push("--no-part".to_string());
+    }
+
+    // Add subtitle arguments
+    let subtitles = options
+        .subtitles
+        .as_ref()
+        .filter(|subs| subs.write || subs.auto_generated || subs.embed);
+    if let Some(subs) = subtitles {
+        if subs.write {
+            args.push("--write-subs".to_string());
+        }
+        if subs.auto_generated {
+            args.push("--write-auto-subs".to_string());
+        }
+        // Embedded subtitles are removed afterwards unless also written
+        if subs.embed {
+            args.push("--embed-subs".to_string());
+        }
+        let sub_langs = subs.sub_langs();
+        if let Some(langs) = &sub_langs {
+            args.push("--sub-langs".to_string());
+            args.push(langs.clone());
+        }
+        // yt-dlp replaces the originals when converting, so conversions
+        // that keep them are done after the download instead
+        if let Some(sub_format) = &subs.format {
+            if !subs.keep_original {
+                args.push("--convert-subs".to_string());
+                args.push(sub_format.clone());
+            }
+        } else if !format.is_video() {
+            // Sidecar subtitles next to audio: SRT is what players pick up
+            args.push("--convert-subs".to_string());
+            args.push("srt".to_string());
+        }
+
+        log.push(LogPayload {
+            level: "info".to_string(),
+            message: format!(
+                "Fetching subtitles: {}{}",
+                sub_langs.as_deref().unwrap_or("default languages"),
+                if subs.auto_generated { " (including auto-generated)" } else { "" }
+            ),
+        });
+    }
+
+    // Cover art next to each file and/or inside it, converted from webp
+    // first: players ignore webp and FLAC can't embed it at all
+    if options.write_thumbnail {
+        args.push("--write-thumbnail".to_string());
+    }
+    let embed_thumbnail = options.embed_thumbnail && format.thumbnail_embedding().is_some();
+    if options.embed_thumbnail {
+        let (level, message) = match format.thumbnail_embedding() {
+            Some(ThumbnailEmbedding::CoverArt) => ("info", "Embedding thumbnail as cover art"),
+            Some(ThumbnailEmbedding::Attachment) => ("info", "Embedding thumbnail as a Matroska attachment"),
+            None => ("warn", "WebM can't hold an embedded thumbnail; skipping it"),
+        };
+        log.push(LogPayload {
+            level: level.to_string(),
+            message: message.to_string(),
+        });
+    }
+    if embed_thumbnail {
+        args.push("--embed-thumbnail".to_string());
+    }
+    if options.write_thumbnail || embed_thumbnail {
+        args.push("--convert-thumbnails".to_string());
+        args.push("jpg".to_string());
+    }
+
+    // ID3 / Vorbis comment tags from the video's metadata
+    if options.embed_metadata {
+        args.push("--embed-metadata".to_string());
+    }
+
+    // Add authentication and account interaction arguments
+    if let Some(cookies) = &options.cookies_file {
+        args.push("--cookies".to_string());
+        args.push(cookies.clone());
+    }
+    if options.mark_watched {
+        if options.cookies_file.is_some() {
+            args.push("--mark-watched".to_string());
+        } else {
+            log.push(LogPayload {
+                level: "warn".to_string(),
+                message: "Mark as watched needs a cookies file; skipping".to_string(),
+            });
+        }
+    }
+
+    // Mutual TLS for servers and proxies that demand a client certificate
+    if let Some(cert) = &options.client_certificate {
+        args.push("--client-certificate".to_string());
+        args.push(cert.clone());
+        if let Some(key) = &options.client_certificate_key {
+            args.push("--client-certificate-key".to_string());
+            args.push(key.clone());
+        }
+    }
+
+    // Skip (and record) items via the download archive
+    args.extend(settings.archive_args.iter().cloned());
+
+    // Add ffmpeg location (our bundled ffmpeg)
+    if let Some(ffmpeg_path) = &settings.ffmpeg_path {
+        if let Some(bin_dir) = ffmpeg_path.parent() {
+            let bin_dir_str = bin_dir.to_string_lossy().to_string();
+            log.push(LogPayload {
+                level: "info".to_string(),
+                message: format!("FFmpeg location: {} (exists: {})", bin_dir_str, ffmpeg_path.exists()),
+            });
+            args.push("--ffmpeg-location".to_string());
+            args.push(bin_dir_str);
+        }
+    }
+
+    // Add URL
+    args.push(url.to_string());
+
+    args
 }
 
 /// Start download command
+///
+/// `download_id` lets the caller cancel it later; one is generated (and
+/// announced in `download-started`) when omitted.
 #[tauri::command]
 pub async fn start_download(
     url: String,
-    format: AudioFormat,
+    format: DownloadFormat,
+    options: Option<OptionOverrides>,
+    download_id: Option<String>,
     app: tauri::AppHandle,
 ) -> Result<DownloadResult, DownloadError> {
-    // Validate URL
+    let options = crate::profiles::resolve_options(&app, &url, &options.unwrap_or_default())?;
+    let download_id = download_id.unwrap_or_else(new_download_id);
+    execute_download(app, download_id, url, format, options).await
+}
+
+/// Continue an interrupted playlist download after its last finished entry
+///
+/// Uses the format and options the playlist was started with.
+#[tauri::command]
+pub async fn resume_playlist(
+    url: String,
+    download_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<DownloadResult, DownloadError> {
+    let resume = crate::playlist::load_resume(&app, &url).ok_or_else(|| {
+        DownloadError::InvalidOption(format!("No interrupted playlist download for {}", url))
+    })?;
+
+    // The saved options already had the profile applied, so they override it whole
+    let options = crate::profiles::resolve_options(
+        &app,
+        &url,
+        &OptionOverrides::from_options(&DownloadOptions {
+            playlist: true,
+            playlist_start: Some(resume.last_index + 1),
+            ..resume.options
+        }),
+    )?;
+    let download_id = download_id.unwrap_or_else(new_download_id);
+    execute_download(app, download_id, url, resume.format, options).await
+}
+
+/// Cancel a running download, killing its yt-dlp process
+#[tauri::command]
+pub fn cancel_download(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if state.active_downloads.cancel(&id) {
+        Ok(())
+    } else {
+        Err(format!("No running download with id {}", id))
+    }
+}
+
+/// File in a staging dir recording which URL and format it belongs to
+#[cfg(not(target_os = "android"))]
+const STAGING_MARKER: &str = ".ydu-source";
+
+#[cfg(not(target_os = "android"))]
+fn staging_marker(url: &str, format: DownloadFormat) -> String {
+    format!("{}\n{}", format.as_str(), url)
+}
+
+/// Downloaded media in `dir` that still has to be converted to `format`
+#[cfg(not(target_os = "android"))]
+fn find_finished_original(dir: &std::path::Path, format: DownloadFormat) -> Option<PathBuf> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files).ok()?;
+    files.into_iter().find(|path| {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        is_media_file(path)
+            && !matches!(ext.as_str(), "part" | "ytdl")
+            && ext != format.as_str()
+            && path.file_name().and_then(|n| n.to_str()) != Some(STAGING_MARKER)
+    })
+}
+
+/// Staging dirs left in `download_dir` by earlier runs of this URL and format
+/// that are no longer being written
+#[cfg(not(target_os = "android"))]
+fn leftover_staging_dirs(
+    state: &AppState,
+    download_dir: &std::path::Path,
+    url: &str,
+    format: DownloadFormat,
+) -> Vec<PathBuf> {
+    let marker = staging_marker(url, format);
+    let Ok(entries) = std::fs::read_dir(download_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter(|dir| {
+            let Some(id) = dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(".ydu-"))
+            else {
+                return false;
+            };
+            // Still being written by a running download
+            !state.active_downloads.contains(id)
+                && std::fs::read_to_string(dir.join(STAGING_MARKER)).ok().as_deref()
+                    == Some(marker.as_str())
+        })
+        .collect()
+}
+
+/// Staging dir left by an earlier run of this download whose media finished
+/// but whose audio extraction did not
+#[cfg(not(target_os = "android"))]
+fn find_interrupted_conversion(
+    state: &AppState,
+    download_dir: &std::path::Path,
+    url: &str,
+    format: DownloadFormat,
+    options: &DownloadOptions,
+) -> Option<(PathBuf, PathBuf)> {
+    if format.audio().is_none() || options.playlist {
+        return None;
+    }
+
+    leftover_staging_dirs(state, download_dir, url, format)
+        .into_iter()
+        .find_map(|dir| {
+            let original = find_finished_original(&dir, format)?;
+            Some((dir, original))
+        })
+}
+
+/// Staging dir left by an earlier run of this download that still holds
+/// partial files yt-dlp can continue
+#[cfg(not(target_os = "android"))]
+fn find_partial_download(
+    state: &AppState,
+    download_dir: &std::path::Path,
+    url: &str,
+    format: DownloadFormat,
+) -> Option<PathBuf> {
+    leftover_staging_dirs(state, download_dir, url, format)
+        .into_iter()
+        .find(|dir| {
+            let mut files = Vec::new();
+            collect_files(dir, &mut files).is_ok()
+                && files.iter().any(|path| {
+                    path.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| e.eq_ignore_ascii_case("part"))
+                })
+        })
+}
+
+/// Convert media left by an interrupted run with the bundled ffmpeg and
+/// finalize it like a normal download
+///
+/// On failure the staging dir is kept so the next attempt can try again.
+#[cfg(not(target_os = "android"))]
+async fn finish_interrupted_conversion(
+    app: &tauri::AppHandle,
+    download_id: &str,
+    url: &str,
+    format: DownloadFormat,
+    staging_dir: &std::path::Path,
+    original: &std::path::Path,
+    download_dir: &std::path::Path,
+) -> Result<DownloadResult, DownloadError> {
+    let state = app.state::<AppState>();
+    let audio = format
+        .audio()
+        .ok_or_else(|| DownloadError::InvalidOption("Not an audio format".to_string()))?;
+
+    let _ = app.emit(
+        "download-log",
+        LogPayload {
+            level: "info".to_string(),
+            message: format!(
+                "Converting media from an interrupted download: {}",
+                original.to_string_lossy()
+            ),
+        },
+    );
+
+    let output = original.with_extension(audio.as_str());
+    crate::commands::media::transcode_audio(app, original, &output, audio, None)
+        .await
+        .map_err(DownloadError::DownloadFailed)?;
+    let _ = std::fs::remove_file(original);
+    let _ = std::fs::remove_file(staging_dir.join(STAGING_MARKER));
+
+    let final_files = finalize_staged_files(staging_dir, download_dir)?;
+    let output_path = final_files
+        .iter()
+        .find(|path| is_media_file(path))
+        .cloned()
+        .unwrap_or(output);
+    let output_path = output_path.to_string_lossy().to_string();
+    state.session_downloads.record(&output_path);
+    state.url_cooldowns.clear(url);
+    // The run that fetched the media failed, so it was never counted
+    let _ = safety::record_download(app, url, 1).await;
+
+    state.event_throttle.emit_final(
+        app,
+        "download-progress",
+        download_id,
+        ProgressPayload {
+            download_id: download_id.to_string(),
+            progress: 100.0,
+            status: "Complete!".to_string(),
+            speed_bytes_per_sec: None,
+            eta_secs: Some(0),
+            speed: None,
+            eta: Some("00:00".to_string()),
+            playlist_index: None,
+        },
+    );
+
+    // Tags come from the original; the info lookup only fills in the result
+    let metadata = fetch_info_json(app, url)
+        .await
+        .ok()
+        .and_then(|info| serde_json::from_value(info).ok())
+        .map(metadata_result)
+        .unwrap_or_default();
+    let title = if metadata.title.is_empty() {
+        std::path::Path::new(&output_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Unknown".to_string())
+    } else {
+        metadata.title.clone()
+    };
+
+    Ok(DownloadResult {
+        title,
+        output_path,
+        subtitle_paths: final_files
+            .iter()
+            .filter(|path| is_subtitle_file(path))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        ..metadata
+    })
+}
+
+/// Tell the frontend a download was cancelled
+#[cfg(not(target_os = "android"))]
+fn emit_cancelled(app: &tauri::AppHandle, download_id: &str, url: &str) {
+    let _ = app.emit(
+        "download-cancelled",
+        DownloadIdPayload {
+            download_id: download_id.to_string(),
+            url: url.to_string(),
+        },
+    );
+}
+
+/// Retries after a network failure when the setting is unset
+const DEFAULT_DOWNLOAD_RETRIES: u64 = 3;
+/// Upper bound for the retry setting
+pub const MAX_DOWNLOAD_RETRIES: u64 = 10;
+/// Wait before the first retry, doubled for each further attempt
+const RETRY_BASE_DELAY_SECS: u64 = 2;
+/// Longest backoff between two attempts (a server's Retry-After can ask for more)
+const RETRY_MAX_DELAY_SECS: u64 = 60;
+
+/// Payload for `download-retry` events
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RetryPayload {
+    download_id: String,
+    /// 1-based number of the attempt about to start
+    attempt: u32,
+    max_attempts: u32,
+    delay_secs: u64,
+    error: String,
+}
+
+/// Run a single download to completion
+///
+/// Shared by the `start_download` command and the queue worker. Network
+/// failures are retried with exponential backoff, resuming the partial files
+/// left in the staging dir. Failures other than cancellation are kept in the
+/// recent-errors history.
+pub(crate) async fn execute_download(
+    app: tauri::AppHandle,
+    download_id: String,
+    url: String,
+    format: DownloadFormat,
+    options: DownloadOptions,
+) -> Result<DownloadResult, DownloadError> {
+    let state = app.state::<AppState>();
+    let max_attempts = crate::settings::get_u64(
+        &app,
+        crate::settings::DOWNLOAD_RETRIES,
+        DEFAULT_DOWNLOAD_RETRIES,
+    )
+    .min(MAX_DOWNLOAD_RETRIES) as u32
+        + 1;
+
+    // Cancellable from here on, including between attempts
+    #[cfg(not(target_os = "android"))]
+    let _tracked = state.active_downloads.track(&download_id).ok_or_else(|| {
+        DownloadError::InvalidOption(format!("Download {} is already running", download_id))
+    })?;
+
+    let mut attempt = 1;
+    let result = loop {
+        let result = run_download(
+            app.clone(),
+            download_id.clone(),
+            url.clone(),
+            format,
+            options.clone(),
+            attempt == max_attempts,
+        )
+        .await;
+
+        let error = match result {
+            Err(DownloadError::NetworkError(error)) if attempt < max_attempts => error,
+            result => break result,
+        };
+
+        // Doubles per attempt, but never retries before a server-requested
+        // Retry-After (recorded as the URL's cooldown) has passed
+        let backoff_secs =
+            (RETRY_BASE_DELAY_SECS << (attempt - 1).min(5)).min(RETRY_MAX_DELAY_SECS);
+        let delay_secs = state
+            .url_cooldowns
+            .remaining_secs(&url)
+            .map_or(backoff_secs, |retry_after_secs| backoff_secs.max(retry_after_secs));
+        attempt += 1;
+        let _ = app.emit(
+            "download-retry",
+            RetryPayload {
+                download_id: download_id.clone(),
+                attempt,
+                max_attempts,
+                delay_secs,
+                error: error.clone(),
+            },
+        );
+        let _ = app.emit(
+            "download-log",
+            LogPayload {
+                level: "warn".to_string(),
+                message: format!(
+                    "Network error ({}); retrying in {}s (attempt {}/{})",
+                    error, delay_secs, attempt, max_attempts
+                ),
+            },
+        );
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(delay_secs)) => {}
+            _ = state.network.stopped() => break Err(DownloadError::NetworkStopped),
+            _ = state.active_downloads.cancelled(&download_id) => {
+                emit_cancelled(&app, &download_id, &url);
+                break Err(DownloadError::Cancelled);
+            }
+        }
+    };
+
+    match &result {
+        // An archive skip downloaded nothing, so it has no history entry
+        Ok(download) if download.skipped => {}
+        Ok(download) => {
+            let _ = crate::history::record_download(&app, &url, format, download).await;
+        }
+        Err(DownloadError::Cancelled) => {}
+        Err(e) => {
+            app.state::<AppState>()
+                .recent_errors
+                .record(e.category(), e.to_string(), &url);
+        }
+    }
+    result
+}
+
+/// One download attempt; unless it is the `final_attempt`, a network failure
+/// keeps its partial files so the retry can resume them
+async fn run_download(
+    app: tauri::AppHandle,
+    download_id: String,
+    url: String,
+    format: DownloadFormat,
+    options: DownloadOptions,
+    final_attempt: bool,
+) -> Result<DownloadResult, DownloadError> {
+    let state = app.state::<AppState>();
+
+    // Validate URL and options (already layered over the site profile)
     validate_url(&url)?;
+    options.validate(format)?;
+
+    if state.network.is_stopped() {
+        return Err(DownloadError::NetworkStopped);
+    }
+
+    // Refuse to hammer a URL that just failed
+    if let Some(retry_after_secs) = state.url_cooldowns.remaining_secs(&url) {
+        return Err(DownloadError::Cooldown { retry_after_secs });
+    }
 
     // Check safety gate
-    let gate_status = safety::should_allow_download(&app);
+    let gate_status = safety::should_allow_download(&app, &url);
     if matches!(gate_status, safety::GateStatus::Locked) {
         return Err(DownloadError::GateLocked);
     }
 
     #[cfg(target_os = "android")]
     {
+        // The plugin runs downloads in-process, so they can't be cancelled
+        let _ = (download_id, final_attempt);
+
         // Android: Use the ytdlp plugin which handles progress internally
         let response = app.ytdlp().download(plugin_models::DownloadRequest {
             url: url.clone(),
             format: Some(format.as_str().to_string()),
-            quality: Some("0".to_string()), // Best
+            quality: Some(options.audio_quality.as_str().to_string()),
             output_dir: None, // Default to Music/Downloads
         }).map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
 
         if !response.success {
+            state.url_cooldowns.record_failure(&url, None);
             return Err(DownloadError::DownloadFailed(response.output.unwrap_or_else(|| "Unknown error".to_string())));
         }
+        state.url_cooldowns.clear(&url);
 
         // Return result
         Ok(DownloadResult {
@@ -218,14 +2055,40 @@ pub async fn start_download(
             duration: None,
             thumbnail_path: None,
             output_path: response.output.unwrap_or_default(),
+            ..Default::default()
         })
     }
 
     #[cfg(not(target_os = "android"))]
     {
+        // Get download directory
+        let download_dir = get_download_dir(&app);
+        std::fs::create_dir_all(&download_dir).ok();
+
+        // A previous run that got the media but not the conversion only needs
+        // ffmpeg again, not another download
+        if let Some((dir, original)) =
+            find_interrupted_conversion(&state, &download_dir, &url, format, &options)
+        {
+            return finish_interrupted_conversion(
+                &app,
+                &download_id,
+                &url,
+                format,
+                &dir,
+                &original,
+                &download_dir,
+            )
+            .await;
+        }
+
         // Load anti-ban config and apply random delay
-        let anti_ban_config = crate::anti_ban::load_config(&app);
+        let anti_ban_config = crate::anti_ban::effective_config(&app, options.anti_ban_profile);
         crate::anti_ban::apply_random_delay(&anti_ban_config).await;
+        if state.active_downloads.is_cancelled(&download_id) {
+            emit_cancelled(&app, &download_id, &url);
+            return Err(DownloadError::Cancelled);
+        }
 
         // Emit log about delay
         if anti_ban_config.enable_delays {
@@ -242,13 +2105,71 @@ pub async fn start_download(
         let yt_dlp_path = get_sidecar_path(&app, SidecarType::YtDlp)
             .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
 
-        // Get download directory
-        let download_dir = get_download_dir(&app);
-        std::fs::create_dir_all(&download_dir).ok();
+        // yt-dlp silently skips embedding without ffmpeg, so fail loudly instead
+        if options.embed_thumbnail
+            && format.thumbnail_embedding().is_some()
+            && !is_sidecar_available(&app, SidecarType::Ffmpeg)
+        {
+            return Err(DownloadError::SidecarError(
+                "ffmpeg is required to embed cover art".to_string(),
+            ));
+        }
+        let sponsorblock = crate::sponsorblock::effective_config(&app, &options.sponsorblock_remove);
+        if sponsorblock.is_active() && !is_sidecar_available(&app, SidecarType::Ffmpeg) {
+            return Err(DownloadError::SidecarError(
+                "ffmpeg is required to cut SponsorBlock segments; install it from the setup screen"
+                    .to_string(),
+            ));
+        }
+
+        // Prefetch metadata alongside the download so the UI can show track
+        // details immediately (cached if get_video_info already ran)
+        // (playlists are described per entry instead)
+        let prefetch = (!options.playlist).then(|| {
+            let app = app.clone();
+            let url = url.clone();
+            tauri::async_runtime::spawn(async move {
+                let info = fetch_info_json(&app, &url).await.ok()?;
+                let metadata = metadata_result(serde_json::from_value(info).ok()?);
+                let _ = app.emit("download-info", metadata.clone());
+                Some(metadata)
+            })
+        });
+
+        // Stage output in a hidden per-download dir so a half-written or
+        // failed conversion never sits in the download dir under its final name
+        let staging_dir = download_dir.join(format!(".ydu-{}", download_id));
+
+        // Pick up partial files from a run that was interrupted, e.g. by the
+        // app closing, so the download continues instead of starting over
+        if !staging_dir.exists()
+            && crate::settings::get_bool(&app, crate::settings::RESUME_DOWNLOADS, true)
+        {
+            if let Some(partial) = find_partial_download(&state, &download_dir, &url, format) {
+                if std::fs::rename(&partial, &staging_dir).is_ok() {
+                    let _ = app.emit(
+                        "download-log",
+                        LogPayload {
+                            level: "info".to_string(),
+                            message: "Resuming partial download from an earlier run".to_string(),
+                        },
+                    );
+                }
+            }
+        }
 
-        // Build output template
-        let output_template = download_dir
-            .join("%(title)s.%(ext)s")
+        // Build output template; library folders or a preset picked for this
+        // download win over the saved custom template
+        let custom_template = if options.library_mode {
+            Some(LIBRARY_TEMPLATE.to_string())
+        } else {
+            (options.filename_scheme == FilenameScheme::default())
+                .then(|| crate::settings::get_string(&app, crate::settings::FILENAME_TEMPLATE))
+                .flatten()
+                .and_then(|t| normalize_filename_template(&t).ok())
+        };
+        let output_template = staging_dir
+            .join(custom_template.as_deref().unwrap_or(options.filename_scheme.template()))
             .to_string_lossy()
             .to_string();
 
@@ -261,100 +2182,206 @@ pub async fn start_download(
             },
         );
 
-        // Build command arguments
-        let mut args: Vec<String> = vec![
-            "--extract-audio".to_string(),
-            "--audio-format".to_string(),
-            format.as_str().to_string(),
-            "--output".to_string(),
-            output_template.clone(),
-            "--no-playlist".to_string(),  // Single video only
-            "--newline".to_string(),      // Progress on new lines
-            "--no-colors".to_string(),    // Clean output for parsing
-        ];
-
-        // Add quality arguments
-        for arg in format.quality_args() {
-            args.push(arg.to_string());
-        }
-
-        // Add proxy arguments
-        let proxy_config = crate::proxy::load_proxy_config(&app);
+        // Rotate through the proxy pool when enabled
+        let proxy_config = crate::proxy::select_proxy(&app);
         if proxy_config.is_enabled() {
-            args.extend(proxy_config.to_ytdlp_args());
             let _ = app.emit(
                 "download-log",
                 LogPayload {
                     level: "info".to_string(),
-                    message: format!("Using proxy: {}:{}", proxy_config.host, proxy_config.port),
+                    message: format!("Using proxy: {}", proxy_config.address()),
                 },
             );
         }
-
-        // Add User-Agent arguments
-        let anti_ban_config = crate::anti_ban::load_config(&app);
-        if anti_ban_config.rotate_user_agent {
-            args.extend(anti_ban_config.to_ytdlp_args());
-            let _ = app.emit(
-                "download-log",
-                LogPayload {
-                    level: "info".to_string(),
-                    message: "Using rotated User-Agent".to_string(),
-                },
-            );
-        }
-
-        // Add ffmpeg location (our bundled ffmpeg)
-        if let Ok(ffmpeg_path) = get_sidecar_path(&app, SidecarType::Ffmpeg) {
-            if let Some(bin_dir) = ffmpeg_path.parent() {
-                let bin_dir_str = bin_dir.to_string_lossy().to_string();
-                let _ = app.emit(
-                    "download-log",
-                    LogPayload {
-                        level: "info".to_string(),
-                        message: format!("FFmpeg location: {} (exists: {})", bin_dir_str, ffmpeg_path.exists()),
-                    },
-                );
-                args.push("--ffmpeg-location".to_string());
-                args.push(bin_dir_str);
-            }
+        let mut log = Vec::new();
+        let args = build_download_args(&app, &url, format, &options, &proxy_config, &output_template, &mut log);
+        for entry in log {
+            let _ = app.emit("download-log", entry);
         }
 
-        // Add URL
-        args.push(url.clone());
-
         // Execute command using shell plugin with STREAMING output for real-time progress
         use tauri_plugin_shell::ShellExt;
+        std::fs::create_dir_all(&staging_dir)
+            .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
+        let _ = std::fs::write(staging_dir.join(STAGING_MARKER), staging_marker(&url, format));
         let shell = app.shell();
-        let (mut rx, _child) = shell
+        let (mut rx, child) = shell
             .command(yt_dlp_path.to_string_lossy().to_string())
             .args(&args)
             .spawn()
-            .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
+            .map_err(|e| {
+                let _ = std::fs::remove_dir_all(&staging_dir);
+                DownloadError::DownloadFailed(e.to_string())
+            })?;
+        if !state.active_downloads.register(&download_id, child) {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            emit_cancelled(&app, &download_id, &url);
+            return Err(DownloadError::Cancelled);
+        }
+        let _ = app.emit(
+            "download-started",
+            DownloadIdPayload {
+                download_id: download_id.clone(),
+                url: url.clone(),
+            },
+        );
 
         // Collect output while streaming progress updates in real-time
         let mut stdout_buffer = String::new();
         let mut stderr_buffer = String::new();
         let mut last_progress: f64 = 0.0;
+        let mut throughput = ThroughputTracker::default();
+        // Resume offset waiting for the next progress line to report the total
+        let emit_resume_events =
+            crate::settings::get_bool(&app, crate::settings::EMIT_RESUME_EVENTS, true);
+        let mut pending_resume: Option<u64> = None;
+        let progress_interval = crate::events::progress_interval(&app);
+        // Items fetched over the network, which is what the safety gate counts.
+        // yt-dlp reports a destination per stream (twice for a merged
+        // video+audio), so an item counts once when its entry ends
+        let mut completed_items: u32 = 0;
+        let mut item_fetched = false;
+        // Items yt-dlp skipped because they were already downloaded
+        let mut skipped_items: u32 = 0;
+        let mut filtered_by_date: u32 = 0;
+        // Playlist entry announced but not yet reported, waiting for its title
+        let mut pending_item: Option<(u32, u32)> = None;
+        let mut playlist_index: Option<u32> = None;
+        let emit_playlist_progress = |(index, total): (u32, u32), current_title: Option<String>| {
+            let _ = app.emit(
+                "playlist-progress",
+                PlaylistProgressPayload {
+                    download_id: download_id.clone(),
+                    index,
+                    total,
+                    current_title,
+                },
+            );
+        };
 
         use tauri_plugin_shell::process::CommandEvent;
-        while let Some(event) = rx.recv().await {
+        loop {
+            let event = tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                // Panic stop: kill yt-dlp and discard the partial output
+                _ = state.network.stopped() => {
+                    if let Some(child) = state.active_downloads.take(&download_id) {
+                        let _ = child.kill();
+                    }
+                    state.event_throttle.forget("download-progress", &download_id);
+                    let _ = std::fs::remove_dir_all(&staging_dir);
+                    return Err(DownloadError::NetworkStopped);
+                }
+            };
             match event {
                 CommandEvent::Stdout(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes);
                     stdout_buffer.push_str(&line);
+
+                    if emit_resume_events {
+                        if let Some(offset) = parse_resume_offset(&line) {
+                            pending_resume = Some(offset);
+                        } else if let Some(resume_bytes) = pending_resume {
+                            if parse_progress(&line).is_some() {
+                                pending_resume = None;
+                                let total_bytes = parse_total_size(&line);
+                                let _ = app.emit(
+                                    "download-resumed",
+                                    ResumePayload {
+                                        resume_bytes,
+                                        total_bytes,
+                                        progress: total_bytes
+                                            .filter(|total| *total > 0)
+                                            .map(|total| resume_bytes as f64 / total as f64 * 100.0),
+                                    },
+                                );
+                            }
+                        }
+                    }
                     
+                    if let Some(item) = parse_playlist_item(&line) {
+                        // The entry before this one is done (downloaded,
+                        // skipped or failed), so a restart can begin here
+                        if item.0 > 1 {
+                            let offset = options.playlist_start.unwrap_or(1) - 1;
+                            let _ = crate::playlist::save_resume(
+                                &app,
+                                &url,
+                                crate::playlist::PlaylistResume {
+                                    last_index: offset + item.0 - 1,
+                                    total: offset + item.1,
+                                    format,
+                                    options: DownloadOptions {
+                                        playlist_start: None,
+                                        ..options.clone()
+                                    },
+                                },
+                            )
+                            .await;
+                        }
+                        if std::mem::take(&mut item_fetched) {
+                            completed_items += 1;
+                        }
+                        // Each entry reports its own 0-100% progress
+                        playlist_index = Some(item.0);
+                        last_progress = 0.0;
+                        // An entry that never reached a destination was skipped
+                        if let Some(skipped) = pending_item.replace(item) {
+                            emit_playlist_progress(skipped, None);
+                        }
+                    } else if let Some(title) = parse_destination_title(&line) {
+                        item_fetched = true;
+                        if let Some(item) = pending_item.take() {
+                            emit_playlist_progress(item, Some(title));
+                        }
+                    }
+
+                    if let Some(selected) = parse_selected_format(&line) {
+                        let _ = app.emit(
+                            "download-log",
+                            LogPayload {
+                                level: "info".to_string(),
+                                message: format!("Selected format: {}", selected),
+                            },
+                        );
+                    }
+
+                    if line.contains("upload date is not in range") {
+                        filtered_by_date += 1;
+                    } else if line.contains("has already been downloaded")
+                        || line.contains("has already been recorded in the archive")
+                    {
+                        skipped_items += 1;
+                    }
+
                     // Emit progress updates in real-time
-                    if let Some(progress) = parse_progress(&line) {
+                    if let Some(info) = parse_progress(&line) {
+                        let progress = info.progress;
+                        if let Some(total) = parse_total_size(&line) {
+                            throughput.record(total as f64 * progress / 100.0);
+                        }
+
                         // Only emit if progress changed significantly (avoid spam)
                         if (progress - last_progress).abs() >= 0.5 || progress >= 99.0 {
                             last_progress = progress;
-                            let _ = app.emit(
+                            state.event_throttle.emit(
+                                &app,
                                 "download-progress",
+                                &download_id,
                                 ProgressPayload {
+                                    download_id: download_id.clone(),
                                     progress,
                                     status: format!("Downloading: {:.1}%", progress),
+                                    speed_bytes_per_sec: info.speed_bytes_per_sec,
+                                    eta_secs: info.eta_secs,
+                                    speed: info.speed,
+                                    eta: info.eta,
+                                    playlist_index,
                                 },
+                                progress_interval,
                             );
                         }
                     }
@@ -363,56 +2390,444 @@ pub async fn start_download(
                     stderr_buffer.push_str(&String::from_utf8_lossy(&line_bytes));
                 }
                 CommandEvent::Terminated(status) => {
-                    // Exit code 0 = success, anything else = failure
-                    let is_success = status.code == Some(0);
+                    if item_fetched {
+                        completed_items += 1;
+                    }
+                    // A success that printed no destination (e.g. a clip cut
+                    // by ffmpeg) and skipped nothing still fetched its item
+                    if status.code == Some(0)
+                        && completed_items == 0
+                        && skipped_items == 0
+                        && filtered_by_date == 0
+                    {
+                        completed_items = 1;
+                    }
+                    // A killed process is a cancellation, not a failure, even
+                    // for a playlist that already finished some entries
+                    if state.active_downloads.is_cancelled(&download_id) {
+                        state.event_throttle.forget("download-progress", &download_id);
+                        let _ = std::fs::remove_dir_all(&staging_dir);
+                        emit_cancelled(&app, &download_id, &url);
+                        return Err(DownloadError::Cancelled);
+                    }
+                    // Exit code 0 = success, anything else = failure; a
+                    // playlist that got some entries through is a partial success
+                    let is_success =
+                        status.code == Some(0) || (options.playlist && completed_items > 0);
                     if !is_success {
-                        let error_msg = if stderr_buffer.is_empty() {
-                            format!("Process exited with code {:?}", status.code)
+                        state.event_throttle.forget("download-progress", &download_id);
+                        let error = if stderr_buffer.is_empty() {
+                            DownloadError::DownloadFailed(format!(
+                                "Process exited with code {:?}",
+                                status.code
+                            ))
                         } else {
-                            stderr_buffer.lines().last().unwrap_or("Download failed").to_string()
+                            classify_failure(&stderr_buffer)
                         };
-                        return Err(DownloadError::DownloadFailed(error_msg));
+                        let retry_after = parse_retry_after(&stderr_buffer);
+                        // The retry resumes the partial files left in staging;
+                        // a server-requested wait is kept so it holds the retry back
+                        if matches!(error, DownloadError::NetworkError(_)) && !final_attempt {
+                            if retry_after.is_some() {
+                                state.url_cooldowns.record_failure(&url, retry_after);
+                            }
+                            return Err(error);
+                        }
+
+                        state.url_cooldowns.record_failure(&url, retry_after);
+                        // Keep finished media so a retry only redoes the conversion
+                        if format.audio().is_some()
+                            && !options.playlist
+                            && find_finished_original(&staging_dir, format).is_some()
+                        {
+                            let _ = app.emit(
+                                "download-log",
+                                LogPayload {
+                                    level: "info".to_string(),
+                                    message: "Kept the downloaded media; retrying will only redo the conversion".to_string(),
+                                },
+                            );
+                        } else {
+                            let _ = std::fs::remove_dir_all(&staging_dir);
+                        }
+                        return Err(error);
+                    }
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(item) = pending_item.take() {
+            emit_playlist_progress(item, None);
+        }
+
+        let stdout = stdout_buffer;
+
+        // Move verified files to their final names
+        let final_files = finalize_staged_files(&staging_dir, &download_dir).inspect_err(|_| {
+            state.event_throttle.forget("download-progress", &download_id);
+        })?;
+
+        let metadata = match prefetch {
+            Some(prefetch) => prefetch.await.ok().flatten(),
+            None => None,
+        };
+
+        // Catch truncated output that still exited cleanly
+        if options.verify_duration && sponsorblock.is_active() {
+            // Removed segments make the file legitimately shorter
+            let _ = app.emit(
+                "download-log",
+                LogPayload {
+                    level: "warn".to_string(),
+                    message: "Skipping duration check: SponsorBlock segments were removed".to_string(),
+                },
+            );
+        } else if options.verify_duration {
+            if let Some(audio) = final_files.iter().find(|path| is_media_file(path)) {
+                // A clip is only as long as the requested part of the video
+                let duration = metadata.as_ref().and_then(|m| m.duration);
+                let expected = match options.clip_range()? {
+                    Some((start, end)) => {
+                        let end = duration.map_or(end, |d| end.min(d as f64));
+                        Some((end - start).max(0.0).round() as u64)
                     }
-                    break;
-                }
-                _ => {}
+                    None => duration,
+                };
+                verify_output_duration(&app, audio, expected).await.inspect_err(|_| {
+                    state.event_throttle.forget("download-progress", &download_id);
+                })?;
             }
         }
 
-        let stdout = stdout_buffer;
+        // Catch silent or undecodable audio from a bad source stream
+        if options.verify_audio {
+            for media in final_files.iter().filter(|path| is_media_file(path)) {
+                verify_audio_content(&app, media).await.inspect_err(|_| {
+                    state.event_throttle.forget("download-progress", &download_id);
+                })?;
+            }
+        }
 
-        // Record successful download
-        let _ = safety::record_download(&app);
+        // Count what was actually downloaded against the daily limit
+        let _ = safety::record_download(&app, &url, completed_items).await;
+        if options.playlist {
+            let _ = crate::playlist::clear_resume(&app, &url).await;
+        }
+        state.url_cooldowns.clear(&url);
 
-        // Emit completion
-        let _ = app.emit(
+        // Emit completion with the achieved speed
+        let average_speed = throughput.average_bytes_per_sec();
+        state.event_throttle.emit_final(
+            &app,
             "download-progress",
+            &download_id,
             ProgressPayload {
+                download_id: download_id.clone(),
                 progress: 100.0,
                 status: "Complete!".to_string(),
+                speed_bytes_per_sec: average_speed,
+                eta_secs: Some(0),
+                speed: average_speed.map(format_speed),
+                eta: Some("00:00".to_string()),
+                playlist_index: None,
             },
         );
+        if let Some(speed) = average_speed {
+            let _ = app.emit(
+                "download-log",
+                LogPayload {
+                    level: "info".to_string(),
+                    message: format!("Average speed: {:.2} MiB/s", speed / (1024.0 * 1024.0)),
+                },
+            );
+        }
 
-        // Extract title from output (simplified parsing)
-        let title = extract_title(&stdout).unwrap_or_else(|| "Unknown".to_string());
+        // Prefer prefetched metadata, fall back to parsing the output
+        let title = metadata
+            .as_ref()
+            .map(|m| m.title.clone())
+            .or_else(|| extract_title(&stdout))
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        // Subtitles written alongside, plus converted copies when keeping originals
+        let mut subtitle_files: Vec<PathBuf> = final_files
+            .iter()
+            .filter(|path| is_subtitle_file(path))
+            .cloned()
+            .collect();
+        let kept_format = options
+            .subtitles
+            .as_ref()
+            .filter(|subs| subs.write && subs.keep_original)
+            .and_then(|subs| subs.format.as_deref());
+        if let Some(sub_format) = kept_format {
+            for original in subtitle_files.clone() {
+                match crate::commands::media::convert_subtitle(&app, &original, sub_format).await {
+                    Ok(Some(converted)) => subtitle_files.push(converted),
+                    Ok(None) => {}
+                    Err(e) => {
+                        let _ = app.emit(
+                            "download-log",
+                            LogPayload {
+                                level: "warn".to_string(),
+                                message: e,
+                            },
+                        );
+                    }
+                }
+            }
+        }
 
-        // Determine output path
-        let output_path = download_dir
-            .join(format!("{}.{}", sanitize_filename(&title), format.as_str()))
+        // Determine output path (nothing is written when the archive skips an item)
+        let output_path = final_files
+            .iter()
+            .find(|path| is_media_file(path))
+            .cloned()
+            .unwrap_or_else(|| download_dir.join(format!("{}.{}", sanitize_filename(&title), format.as_str())))
             .to_string_lossy()
             .to_string();
 
+        // Archive-skipped items wrote nothing this session
+        if !final_files.is_empty() {
+            state.session_downloads.record(&output_path);
+        }
+
+        // One entry per downloaded file, with the errors of entries that failed
+        let (playlist_items, failed_items) = if options.playlist {
+            let items = final_files
+                .iter()
+                .filter(|path| is_media_file(path))
+                .map(|path| playlist_item_result(path, &final_files))
+                .collect();
+            let failed: Vec<String> = stderr_buffer
+                .lines()
+                .filter_map(|line| line.strip_prefix("ERROR:"))
+                .map(|e| e.trim().to_string())
+                .collect();
+            if !failed.is_empty() {
+                let _ = app.emit(
+                    "download-log",
+                    LogPayload {
+                        level: "warn".to_string(),
+                        message: format!("{} playlist item(s) failed", failed.len()),
+                    },
+                );
+            }
+            (items, failed)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        // Written cover art replaces the cached preview thumbnail
+        let mut metadata = metadata.unwrap_or_default();
+        if let Some(cover) = final_files.iter().find(|path| is_thumbnail_file(path)) {
+            metadata.thumbnail_path = Some(cover.to_string_lossy().to_string());
+        }
+
         Ok(DownloadResult {
             title,
-            artist: None, // TODO: Extract from metadata
-            album: None,
-            duration: None,
-            thumbnail_path: None,
             output_path,
+            average_speed_bytes_per_sec: average_speed,
+            filtered_by_date,
+            subtitle_paths: subtitle_files
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            playlist_items,
+            failed_items,
+            skipped: final_files.is_empty(),
+            ..metadata
         })
     }
 }
 
+/// Collect every file under `dir`, recursing into subdirectories
+#[cfg(not(target_os = "android"))]
+fn collect_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Describe one playlist entry from its media file and the files written beside it
+#[cfg(not(target_os = "android"))]
+fn playlist_item_result(media: &std::path::Path, files: &[PathBuf]) -> DownloadResult {
+    let stem = media
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let belongs = |path: &&PathBuf| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().starts_with(&format!("{}.", stem)))
+            .unwrap_or(false)
+    };
+
+    DownloadResult {
+        title: stem.clone(),
+        output_path: media.to_string_lossy().to_string(),
+        thumbnail_path: files
+            .iter()
+            .filter(belongs)
+            .find(|path| is_thumbnail_file(path))
+            .map(|path| path.to_string_lossy().to_string()),
+        subtitle_paths: files
+            .iter()
+            .filter(belongs)
+            .filter(|path| is_subtitle_file(path))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Move finished files from the staging dir into `dest_dir`, keeping their
+/// relative paths, and remove the staging dir
+///
+/// Fails (and discards everything) if any output is empty. Returns the final
+/// paths in the order they were written.
+#[cfg(not(target_os = "android"))]
+fn finalize_staged_files(
+    staging_dir: &std::path::Path,
+    dest_dir: &std::path::Path,
+) -> Result<Vec<PathBuf>, DownloadError> {
+    let io_error = |e: std::io::Error| {
+        let _ = std::fs::remove_dir_all(staging_dir);
+        DownloadError::DownloadFailed(format!("Failed to finalize output: {}", e))
+    };
+
+    let mut staged = Vec::new();
+    if staging_dir.exists() {
+        collect_files(staging_dir, &mut staged).map_err(io_error)?;
+    }
+
+    // Leftover partial files and the marker are never part of the result
+    staged.retain(|path| {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        !matches!(ext, "part" | "ytdl")
+            && path.file_name().and_then(|n| n.to_str()) != Some(STAGING_MARKER)
+    });
+
+    // Sort by write time so multi-file output keeps its download order
+    staged.sort_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+
+    if let Some(empty) = staged
+        .iter()
+        .find(|path| std::fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true))
+    {
+        let name = empty.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let _ = std::fs::remove_dir_all(staging_dir);
+        return Err(DownloadError::DownloadFailed(format!("Output file is empty: {}", name)));
+    }
+
+    let mut final_files = Vec::with_capacity(staged.len());
+    for path in staged {
+        let relative = path.strip_prefix(staging_dir).unwrap_or(&path).to_path_buf();
+        let dest = dest_dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        if dest.exists() {
+            std::fs::remove_file(&dest).map_err(io_error)?;
+        }
+        std::fs::rename(&path, &dest).map_err(io_error)?;
+        final_files.push(dest);
+    }
+
+    let _ = std::fs::remove_dir_all(staging_dir);
+    Ok(final_files)
+}
+
+/// Allowed difference between the output's and the metadata's duration
+#[cfg(not(target_os = "android"))]
+fn duration_tolerance_secs(expected: f64) -> f64 {
+    (expected * 0.02).max(2.0)
+}
+
+/// Check that a finished file is as long as the metadata says
+///
+/// A truncated file is deleted so it can't be mistaken for a good one. When
+/// the expected or actual duration is unknown the check is skipped.
+#[cfg(not(target_os = "android"))]
+async fn verify_output_duration(
+    app: &tauri::AppHandle,
+    path: &std::path::Path,
+    expected: Option<u64>,
+) -> Result<(), DownloadError> {
+    let skip = |reason: String| {
+        let _ = app.emit(
+            "download-log",
+            LogPayload {
+                level: "warn".to_string(),
+                message: format!("Skipping duration check: {}", reason),
+            },
+        );
+        Ok(())
+    };
+
+    let expected = match expected {
+        Some(secs) if secs > 0 => secs as f64,
+        _ => return skip("duration not in metadata".to_string()),
+    };
+    let actual = match crate::commands::media::probe_duration(app, path).await {
+        Ok(secs) => secs,
+        Err(e) => return skip(e),
+    };
+
+    if (expected - actual).abs() > duration_tolerance_secs(expected) {
+        let _ = std::fs::remove_file(path);
+        return Err(DownloadError::IncompleteDownload(format!(
+            "expected {:.0}s of audio but the file has {:.1}s",
+            expected, actual
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check that a finished file has audible, decodable audio
+///
+/// Bad files are deleted so they can't be mistaken for good ones.
+#[cfg(not(target_os = "android"))]
+async fn verify_audio_content(app: &tauri::AppHandle, path: &std::path::Path) -> Result<(), DownloadError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // Without ffmpeg nothing can be measured; don't blame the file for that
+    if !is_sidecar_available(app, SidecarType::Ffmpeg) {
+        let _ = app.emit(
+            "download-log",
+            LogPayload {
+                level: "warn".to_string(),
+                message: "Skipping audio check: ffmpeg is not installed".to_string(),
+            },
+        );
+        return Ok(());
+    }
+
+    let level = crate::commands::media::measure_audio_level(app, path)
+        .await
+        .map_err(|e| {
+            let _ = std::fs::remove_file(path);
+            DownloadError::CorruptOutput(format!("{}: {}", name, e))
+        })?;
+
+    if level.silent {
+        let _ = std::fs::remove_file(path);
+        return Err(DownloadError::CorruptOutput(format!("{} is entirely silent", name)));
+    }
+    Ok(())
+}
+
 /// Extract title from yt-dlp output
 fn extract_title(output: &str) -> Option<String> {
     // Look for "[download] Destination:" line
@@ -443,7 +2858,7 @@ fn extract_title(output: &str) -> Option<String> {
 }
 
 /// Basic filename sanitization
-fn sanitize_filename(name: &str) -> String {
+pub(crate) fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| match c {
             '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
@@ -457,11 +2872,83 @@ fn sanitize_filename(name: &str) -> String {
 struct YtDlpInfo {
     title: String,
     uploader: Option<String>,
+    /// Music metadata, only present for tracks YouTube recognizes as songs
+    track: Option<String>,
+    artist: Option<String>,
+    #[serde(default)]
+    artists: Vec<String>,
     album: Option<String>,
     duration: Option<f64>,
     thumbnail: Option<String>,
 }
 
+/// Run yt-dlp with `args` (proxy added automatically) and parse its JSON stdout
+pub(crate) async fn run_ytdlp_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    mut args: Vec<String>,
+) -> Result<serde_json::Value, DownloadError> {
+    let proxy_config = proxy::resolve_proxy_config(app);
+    if proxy_config.is_enabled() {
+        args.extend(proxy_config.to_ytdlp_args());
+    }
+    args.extend(crate::headers::to_ytdlp_args(app));
+
+    let output = crate::sidecar::run_sidecar(app, SidecarType::YtDlp, &args, None)
+        .await
+        .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
+
+    if !output.success() {
+        return Err(classify_failure(&output.stderr));
+    }
+
+    serde_json::from_str(&output.stdout)
+        .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse metadata: {}", e)))
+}
+
+/// Fetch the full info JSON for a single video (served from the info cache when possible)
+pub(crate) async fn fetch_info_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &str,
+) -> Result<serde_json::Value, DownloadError> {
+    let state = app.state::<AppState>();
+    if let Some(info) = state.info_cache.get(url) {
+        return Ok(info);
+    }
+
+    let info = run_ytdlp_json(
+        app,
+        vec![
+            "--dump-json".to_string(),
+            "--skip-download".to_string(),
+            "--no-playlist".to_string(),
+            url.to_string(),
+        ],
+    )
+    .await?;
+
+    state.info_cache.insert(url, info.clone());
+    Ok(info)
+}
+
+/// Build a metadata-only result (no output path yet) from parsed info
+fn metadata_result(info: YtDlpInfo) -> DownloadResult {
+    // Same precedence yt-dlp uses for the tags it embeds
+    let artist = info
+        .artist
+        .or_else(|| (!info.artists.is_empty()).then(|| info.artists.join(", ")))
+        .or(info.uploader);
+
+    DownloadResult {
+        title: info.track.unwrap_or(info.title),
+        artist,
+        album: info.album,
+        duration: info.duration.map(|d| d as u64),
+        thumbnail_path: info.thumbnail,
+        output_path: String::new(), // Not known yet
+        ..Default::default()
+    }
+}
+
 /// Fetch video metadata without downloading
 #[tauri::command]
 pub async fn get_video_info(
@@ -470,6 +2957,10 @@ pub async fn get_video_info(
 ) -> Result<DownloadResult, DownloadError> {
     // Validate URL
     validate_url(&url)?;
+    app.state::<AppState>()
+        .network
+        .ensure_allowed()
+        .map_err(|_| DownloadError::NetworkStopped)?;
 
     #[cfg(target_os = "android")]
     {
@@ -484,65 +2975,219 @@ pub async fn get_video_info(
             duration: response.duration.map(|d| d as u64),
             thumbnail_path: response.thumbnail,
             output_path: String::new(),
+            ..Default::default()
         });
     }
 
     #[cfg(not(target_os = "android"))]
     {
-        // Get sidecar path
-        let yt_dlp_path = get_sidecar_path(&app, SidecarType::YtDlp)
-            .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
+        let info: YtDlpInfo = serde_json::from_value(fetch_info_json(&app, &url).await?)
+            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse metadata: {}", e)))?;
 
-        // Build command arguments for metadata only
-        let mut args = vec![
-            "--dump-json".to_string(),
-            "--skip-download".to_string(),
-            url.clone(),
-        ];
+        Ok(metadata_result(info))
+    }
+}
 
-        // Add proxy arguments if enabled
-        let proxy_config = crate::proxy::load_proxy_config(&app);
-        if proxy_config.is_enabled() {
-            args.extend(proxy_config.to_ytdlp_args());
-        }
+/// Check whether `download-resumed` events are emitted
+#[tauri::command]
+pub fn get_emit_resume_events(app: tauri::AppHandle) -> bool {
+    crate::settings::get_bool(&app, crate::settings::EMIT_RESUME_EVENTS, true)
+}
 
-        // Spawn command
-        use tauri_plugin_shell::ShellExt;
-        let (mut rx, _child) = app
-            .shell()
-            .sidecar(yt_dlp_path.to_string_lossy().to_string())
-            .map_err(|e| DownloadError::SidecarError(e.to_string()))?
-            .args(args)
-            .spawn()
-            .map_err(|e| DownloadError::SidecarError(e.to_string()))?;
+/// Enable or disable `download-resumed` events
+#[tauri::command]
+pub fn set_emit_resume_events(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    crate::settings::set_value(&app, crate::settings::EMIT_RESUME_EVENTS, serde_json::json!(enabled))
+}
 
-        let mut stdout_buffer = String::new();
+/// Check whether interrupted downloads continue from their partial files
+#[tauri::command]
+pub fn get_resume_downloads(app: tauri::AppHandle) -> bool {
+    crate::settings::get_bool(&app, crate::settings::RESUME_DOWNLOADS, true)
+}
 
-        // Collect stdout
-        use tauri_plugin_shell::process::CommandEvent;
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stdout(line_bytes) => {
-                    stdout_buffer.push_str(&String::from_utf8_lossy(&line_bytes));
-                }
-                CommandEvent::Terminated(_) => break,
-                _ => {}
-            }
-        }
+/// Enable or disable continuing interrupted downloads from their partial files
+#[tauri::command]
+pub fn set_resume_downloads(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    crate::settings::set_value(&app, crate::settings::RESUME_DOWNLOADS, serde_json::json!(enabled))
+}
 
-        // Parse JSON
-        let info: YtDlpInfo = serde_json::from_str(&stdout_buffer)
-            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse metadata: {}", e)))?;
+/// Replace the saved anti-ban settings with a preset; returns the new settings
+///
+/// A custom throttled rate is kept since no preset sets one.
+#[tauri::command]
+pub fn activate_anti_ban_profile(
+    profile: crate::anti_ban::AntiBanProfile,
+    app: tauri::AppHandle,
+) -> Result<crate::anti_ban::AntiBanConfig, String> {
+    let config = profile.apply(&crate::anti_ban::load_config(&app));
+    crate::anti_ban::save_config(&app, &config)?;
+    Ok(config)
+}
 
-        Ok(DownloadResult {
-            title: info.title,
-            artist: info.uploader,
-            album: info.album,
-            duration: info.duration.map(|d| d as u64),
-            thumbnail_path: info.thumbnail,
-            output_path: String::new(), // Not known yet
-        })
+/// Get how often a download is retried after a network failure
+#[tauri::command]
+pub fn get_download_retries(app: tauri::AppHandle) -> u64 {
+    crate::settings::get_u64(&app, crate::settings::DOWNLOAD_RETRIES, DEFAULT_DOWNLOAD_RETRIES)
+        .min(MAX_DOWNLOAD_RETRIES)
+}
+
+/// Set how often a download is retried after a network failure (0 disables retries)
+#[tauri::command]
+pub fn set_download_retries(retries: u64, app: tauri::AppHandle) -> Result<(), String> {
+    if retries > MAX_DOWNLOAD_RETRIES {
+        return Err(format!("Retries must be at most {}", MAX_DOWNLOAD_RETRIES));
+    }
+    crate::settings::set_value(&app, crate::settings::DOWNLOAD_RETRIES, serde_json::json!(retries))
+}
+
+/// Get the custom headers sent with every yt-dlp request
+#[tauri::command]
+pub fn get_custom_headers(app: tauri::AppHandle) -> Vec<crate::headers::CustomHeader> {
+    crate::headers::load_headers(&app)
+}
+
+/// Replace the custom headers; names must be valid HTTP tokens
+#[tauri::command]
+pub fn set_custom_headers(
+    headers: Vec<crate::headers::CustomHeader>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    crate::headers::save_headers(&app, &headers)
+}
+
+/// Get the SponsorBlock segment removal settings
+#[tauri::command]
+pub fn get_sponsorblock_config(app: tauri::AppHandle) -> crate::sponsorblock::SponsorBlockConfig {
+    crate::sponsorblock::load_config(&app)
+}
+
+/// Set the SponsorBlock segment removal settings
+#[tauri::command]
+pub fn set_sponsorblock_config(
+    config: crate::sponsorblock::SponsorBlockConfig,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    crate::sponsorblock::save_config(&app, &config)
+}
+
+/// Get the per-site option profiles
+#[tauri::command]
+pub fn get_site_profiles(app: tauri::AppHandle) -> Vec<crate::profiles::SiteProfile> {
+    crate::profiles::load_profiles(&app)
+}
+
+/// Replace the per-site option profiles
+#[tauri::command]
+pub fn set_site_profiles(
+    profiles: Vec<crate::profiles::SiteProfile>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    crate::profiles::save_profiles(&app, &profiles)
+}
+
+/// Check whether low-footprint mode is enabled
+#[tauri::command]
+pub fn get_low_footprint(app: tauri::AppHandle) -> bool {
+    crate::settings::get_bool(&app, crate::settings::LOW_FOOTPRINT, false)
+}
+
+/// Enable or disable low-footprint mode (applies on next launch)
+#[tauri::command]
+pub fn set_low_footprint(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    crate::settings::set_value(&app, crate::settings::LOW_FOOTPRINT, serde_json::json!(enabled))
+}
+
+/// Get the minimum gap between progress events of one download, in milliseconds
+#[tauri::command]
+pub fn get_progress_event_interval(app: tauri::AppHandle) -> u64 {
+    crate::events::progress_interval(&app).as_millis() as u64
+}
+
+/// Set the minimum gap between progress events of one download, in milliseconds
+#[tauri::command]
+pub fn set_progress_event_interval(interval_ms: u64, app: tauri::AppHandle) -> Result<(), String> {
+    if interval_ms > crate::events::MAX_INTERVAL_MS {
+        return Err(format!(
+            "Interval must be at most {}ms",
+            crate::events::MAX_INTERVAL_MS
+        ));
     }
+    crate::settings::set_value(&app, crate::settings::PROGRESS_EVENT_INTERVAL_MS, serde_json::json!(interval_ms))
+}
+
+/// Get how config changes are written to disk
+#[tauri::command]
+pub fn get_store_save_mode(app: tauri::AppHandle) -> crate::settings::SaveMode {
+    crate::settings::load_save_mode(&app)
+}
+
+/// Set how config changes are written to disk
+#[tauri::command]
+pub fn set_store_save_mode(mode: crate::settings::SaveMode, app: tauri::AppHandle) -> Result<(), String> {
+    crate::settings::set_value(
+        &app,
+        crate::settings::STORE_SAVE_MODE,
+        serde_json::to_value(mode).map_err(|e| format!("Serialization error: {}", e))?,
+    )?;
+
+    // Flush right away so switching to debounced never leaves a pending write
+    crate::settings::flush_all_stores(&app);
+    Ok(())
+}
+
+/// Get the custom output filename template (`None` when using the presets)
+#[tauri::command]
+pub fn get_filename_template(app: tauri::AppHandle) -> Option<String> {
+    crate::settings::get_string(&app, crate::settings::FILENAME_TEMPLATE)
+}
+
+/// Set the custom output filename template, e.g. `%(uploader)s - %(title)s`
+///
+/// `.%(ext)s` is appended when missing; `None` or an empty string restores
+/// the default naming. Returns the template as saved.
+#[tauri::command]
+pub fn set_filename_template(template: Option<String>, app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let template = match template.filter(|t| !t.trim().is_empty()) {
+        Some(t) => Some(normalize_filename_template(&t)?),
+        None => None,
+    };
+    crate::settings::set_value(
+        &app,
+        crate::settings::FILENAME_TEMPLATE,
+        serde_json::json!(template.clone().unwrap_or_default()),
+    )?;
+    Ok(template)
+}
+
+/// Check whether the download archive is enabled
+#[tauri::command]
+pub fn get_download_archive_enabled(app: tauri::AppHandle) -> bool {
+    crate::archive::is_enabled(&app)
+}
+
+/// Enable or disable the download archive
+#[tauri::command]
+pub fn set_download_archive_enabled(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    crate::settings::set_value(&app, crate::archive::USE_ARCHIVE_KEY, serde_json::json!(enabled))
+}
+
+/// List entries recorded in the download archive
+#[tauri::command]
+pub fn list_archive_entries(app: tauri::AppHandle) -> Result<Vec<crate::archive::ArchiveEntry>, String> {
+    crate::archive::list_entries(&app)
+}
+
+/// Remove a single archive entry so that item can be downloaded again
+#[tauri::command]
+pub fn remove_archive_entry(id: String, app: tauri::AppHandle) -> Result<(), String> {
+    crate::archive::remove_entry(&app, &id)
+}
+
+/// Remove every archive entry
+#[tauri::command]
+pub fn clear_download_archive(app: tauri::AppHandle) -> Result<(), String> {
+    crate::archive::clear(&app)
 }
 
 /// Get current download count
@@ -553,8 +3198,32 @@ pub fn get_download_count(app: tauri::AppHandle) -> u32 {
 
 /// Set safety gate bypass
 #[tauri::command]
-pub fn set_gate_bypass(bypass: bool, app: tauri::AppHandle) -> Result<(), String> {
-    safety::set_bypass(&app, bypass)
+pub async fn set_gate_bypass(bypass: bool, app: tauri::AppHandle) -> Result<(), String> {
+    safety::set_bypass(&app, bypass).await
+}
+
+/// Repair an inconsistent safety gate record; returns whether one was found
+#[tauri::command]
+pub async fn repair_safety_gate(app: tauri::AppHandle) -> Result<bool, String> {
+    safety::repair_gate_data(&app).await
+}
+
+/// Report a system clock change that affects the daily gate reset, if any
+#[tauri::command]
+pub fn check_clock_skew(app: tauri::AppHandle) -> Option<safety::ClockJump> {
+    safety::check_clock_skew(&app)
+}
+
+/// Get safety gate configuration
+#[tauri::command]
+pub fn get_safety_config(app: tauri::AppHandle) -> safety::SafetyGateConfig {
+    safety::load_gate_config(&app)
+}
+
+/// Set safety gate configuration
+#[tauri::command]
+pub fn set_safety_config(config: safety::SafetyGateConfig, app: tauri::AppHandle) -> Result<(), String> {
+    safety::save_gate_config(&app, &config)
 }
 
 /// Get proxy configuration
@@ -569,12 +3238,74 @@ pub fn set_proxy_config(config: proxy::ProxyConfig, app: tauri::AppHandle) -> Re
     proxy::save_proxy_config(&app, &config)
 }
 
+/// Check whether proxy env vars are used when no proxy is configured
+#[tauri::command]
+pub fn get_proxy_env_fallback(app: tauri::AppHandle) -> bool {
+    proxy::load_env_fallback_enabled(&app)
+}
+
+/// Enable or disable the proxy env var fallback
+#[tauri::command]
+pub fn set_proxy_env_fallback(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    proxy::save_env_fallback_enabled(&app, enabled)
+}
+
 /// Import proxies from text content
 #[tauri::command]
 pub fn import_proxies(content: String) -> Vec<proxy::ProxyConfig> {
     proxy::parse_proxy_list(&content)
 }
 
+/// Get the persisted proxy pool
+#[tauri::command]
+pub fn get_proxy_pool(app: tauri::AppHandle) -> Vec<proxy::ProxyConfig> {
+    proxy::load_proxy_pool(&app)
+}
+
+/// Replace the persisted proxy pool
+#[tauri::command]
+pub async fn set_proxy_pool(pool: Vec<proxy::ProxyConfig>, app: tauri::AppHandle) -> Result<(), String> {
+    proxy::save_proxy_pool(&app, &pool).await
+}
+
+/// Get how downloads pick a proxy from the pool
+#[tauri::command]
+pub fn get_proxy_rotation(app: tauri::AppHandle) -> proxy::ProxyRotation {
+    proxy::load_rotation(&app)
+}
+
+/// Set how downloads pick a proxy from the pool
+#[tauri::command]
+pub fn set_proxy_rotation(rotation: proxy::ProxyRotation, app: tauri::AppHandle) -> Result<(), String> {
+    proxy::save_rotation(&app, rotation)
+}
+
+/// Test every proxy in the pool and drop dead or slow entries
+#[tauri::command]
+pub async fn prune_proxy_pool(
+    max_latency_ms: Option<u64>,
+    app: tauri::AppHandle,
+) -> Result<proxy::PruneResult, String> {
+    app.state::<AppState>().network.ensure_allowed()?;
+    let pool = proxy::load_proxy_pool(&app);
+    let total = pool.len();
+
+    let healthy = proxy::filter_healthy(
+        pool.clone(),
+        max_latency_ms.unwrap_or(proxy::DEFAULT_MAX_LATENCY_MS),
+    )
+    .await;
+    // The checks take a while; drop only what failed so proxies added
+    // meanwhile survive
+    let dead: Vec<_> = pool.into_iter().filter(|p| !healthy.contains(p)).collect();
+    proxy::remove_from_proxy_pool(&app, &dead).await?;
+
+    Ok(proxy::PruneResult {
+        kept: healthy.len(),
+        removed: total - healthy.len(),
+    })
+}
+
 /// Get anti-ban configuration
 #[tauri::command]
 pub fn get_anti_ban_config(app: tauri::AppHandle) -> crate::anti_ban::AntiBanConfig {
@@ -586,3 +3317,124 @@ pub fn get_anti_ban_config(app: tauri::AppHandle) -> crate::anti_ban::AntiBanCon
 pub fn set_anti_ban_config(config: crate::anti_ban::AntiBanConfig, app: tauri::AppHandle) -> Result<(), String> {
     crate::anti_ban::save_config(&app, &config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed `--dump-json` output for a track YouTube recognizes as a song
+    const MUSIC_INFO: &str = r#"{
+        "id": "dQw4w9WgXcQ",
+        "title": "Rick Astley - Never Gonna Give You Up (Official Video)",
+        "uploader": "Rick Astley",
+        "track": "Never Gonna Give You Up",
+        "artists": ["Rick Astley"],
+        "album": "Whenever You Need Somebody",
+        "duration": 212.0,
+        "thumbnail": "https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg"
+    }"#;
+
+    #[test]
+    fn music_metadata_becomes_tags() {
+        let info: YtDlpInfo = serde_json::from_str(MUSIC_INFO).expect("fixture parses");
+        let result = metadata_result(info);
+
+        assert_eq!(result.title, "Never Gonna Give You Up");
+        assert_eq!(result.artist.as_deref(), Some("Rick Astley"));
+        assert_eq!(result.album.as_deref(), Some("Whenever You Need Somebody"));
+        assert_eq!(result.duration, Some(212));
+    }
+
+    #[test]
+    fn artist_takes_precedence_over_artists_and_uploader() {
+        let info: YtDlpInfo = serde_json::from_str(
+            r#"{"title": "Live set", "uploader": "Channel", "artist": "Main", "artists": ["A", "B"]}"#,
+        )
+        .expect("fixture parses");
+        assert_eq!(metadata_result(info).artist.as_deref(), Some("Main"));
+
+        let info: YtDlpInfo =
+            serde_json::from_str(r#"{"title": "Live set", "uploader": "Channel", "artists": ["A", "B"]}"#)
+                .expect("fixture parses");
+        assert_eq!(metadata_result(info).artist.as_deref(), Some("A, B"));
+    }
+
+    #[test]
+    fn plain_video_falls_back_to_title_and_uploader() {
+        let info: YtDlpInfo =
+            serde_json::from_str(r#"{"title": "Vlog #12", "uploader": "Channel"}"#).expect("fixture parses");
+        let result = metadata_result(info);
+
+        assert_eq!(result.title, "Vlog #12");
+        assert_eq!(result.artist.as_deref(), Some("Channel"));
+        assert_eq!(result.album, None);
+    }
+
+    #[test]
+    fn sent_options_override_profile_even_when_default() {
+        let profile = DownloadOptions {
+            embed_metadata: true,
+            write_thumbnail: true,
+            max_height: Some(720),
+            ..Default::default()
+        };
+        let call: OptionOverrides =
+            serde_json::from_str(r#"{"embed_metadata": false, "max_height": null}"#).expect("overrides parse");
+        let merged = call.merged_over(&profile).expect("merge succeeds");
+
+        assert!(!merged.embed_metadata);
+        assert_eq!(merged.max_height, None);
+        // Not sent, so the profile's value stays
+        assert!(merged.write_thumbnail);
+    }
+
+    #[test]
+    fn subtitle_languages_join_into_sub_langs() {
+        let subs = SubtitleConfig {
+            languages: vec!["en".to_string(), " de ".to_string()],
+            ..Default::default()
+        };
+        assert!(subs.validate(DownloadFormat::Mp4).is_ok());
+        assert_eq!(subs.sub_langs().as_deref(), Some("en,de"));
+        assert_eq!(SubtitleConfig::default().sub_langs(), None);
+    }
+
+    #[test]
+    fn subtitle_languages_reject_empty_and_comma_entries() {
+        for bad in ["", "  ", "en,de"] {
+            let subs = SubtitleConfig {
+                languages: vec!["en".to_string(), bad.to_string()],
+                ..Default::default()
+            };
+            assert!(
+                matches!(subs.validate(DownloadFormat::Mp4), Err(DownloadError::InvalidOption(_))),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "android"))]
+    fn resume_setting_picks_continue_flag() {
+        for (resume, expected, unexpected) in
+            [(true, "--continue", "--no-continue"), (false, "--no-continue", "--continue")]
+        {
+            let settings = ArgSettings {
+                resume,
+                ..Default::default()
+            };
+            let args = build_args_with(
+                &settings,
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                DownloadFormat::Mp3,
+                &DownloadOptions::default(),
+                &proxy::ProxyConfig::default(),
+                "%(title)s.%(ext)s",
+                &mut Vec::new(),
+            );
+            assert!(args.iter().any(|arg| arg == expected), "{:?}", args);
+            assert!(!args.iter().any(|arg| arg == unexpected), "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Download history commands
+
+use serde::Serialize;
+
+use crate::state::AppState;
+
+/// On-disk size of one download from this session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDownloadSize {
+    pub path: String,
+    /// `None` when the file has since been moved or deleted
+    pub size_bytes: Option<u64>,
+    pub missing: bool,
+}
+
+/// Disk usage of everything downloaded this session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDownloadSizes {
+    pub downloads: Vec<SessionDownloadSize>,
+    /// Sum over the files that still exist
+    pub total_bytes: u64,
+}
+
+/// Report the current on-disk size of each download completed this session
+#[tauri::command]
+pub fn get_session_download_sizes(state: tauri::State<'_, AppState>) -> SessionDownloadSizes {
+    let downloads: Vec<SessionDownloadSize> = state
+        .session_downloads
+        .paths()
+        .into_iter()
+        .map(|path| {
+            let size_bytes = std::fs::metadata(&path)
+                .ok()
+                .filter(|m| m.is_file())
+                .map(|m| m.len());
+            SessionDownloadSize {
+                missing: size_bytes.is_none(),
+                size_bytes,
+                path,
+            }
+        })
+        .collect();
+
+    SessionDownloadSizes {
+        total_bytes: downloads.iter().filter_map(|d| d.size_bytes).sum(),
+        downloads,
+    }
+}
+
+/// Most recent downloads first, across sessions
+#[tauri::command]
+pub fn get_download_history(
+    limit: Option<usize>,
+    app: tauri::AppHandle,
+) -> Vec<crate::history::HistoryEntry> {
+    let mut entries = crate::history::load_history(&app);
+    entries.reverse();
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    entries
+}
+
+/// Delete the persisted download history
+#[tauri::command]
+pub async fn clear_download_history(app: tauri::AppHandle) -> Result<(), String> {
+    crate::history::clear_history(&app).await
+}
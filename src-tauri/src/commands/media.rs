@@ -0,0 +1,539 @@
+//! Local media commands
+//! Work on files already on disk using the bundled ffmpeg
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::commands::download::AudioFormat;
+
+/// Audio containers ffmpeg can read that we accept as conversion input
+const SUPPORTED_INPUT_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "m4a", "aac", "ogg", "opus", "wav", "webm", "mka",
+];
+
+/// Peak level at or below which a file counts as silent (ffmpeg reports
+/// digital silence as -91 dB)
+pub(crate) const SILENCE_THRESHOLD_DB: f64 = -90.0;
+
+/// Loudness of a file as measured by ffmpeg's `volumedetect`
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevel {
+    pub mean_volume_db: Option<f64>,
+    pub max_volume_db: Option<f64>,
+    pub silent: bool,
+}
+
+/// Short, long-lived clip ("Me at the zoo", 19s) used as the benchmark sample
+#[cfg(not(target_os = "android"))]
+const BENCHMARK_SAMPLE_URL: &str = "https://www.youtube.com/watch?v=jNQXAC9IVRw";
+
+/// Upper bound for fetching the benchmark sample
+#[cfg(not(target_os = "android"))]
+const BENCHMARK_FETCH_TIMEOUT_SECS: u64 = 120;
+
+/// Time taken to convert the sample to one format
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatTiming {
+    pub format: AudioFormat,
+    pub millis: u64,
+}
+
+/// Result of `benchmark_extraction`
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractionBenchmark {
+    /// Length of the sample, to relate the timings to real-time speed
+    pub sample_duration_secs: Option<f64>,
+    pub timings: Vec<FormatTiming>,
+}
+
+/// Extension of `path`, lowercased
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+}
+
+/// Parse `SS`, `MM:SS` or `HH:MM:SS` (fractional seconds allowed) into seconds
+pub(crate) fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim().split(':') {
+        let n: f64 = part.parse().ok()?;
+        if !n.is_finite() || n < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + n;
+    }
+    Some(seconds)
+}
+
+/// Path of the ffprobe binary shipped next to the bundled ffmpeg
+#[cfg(not(target_os = "android"))]
+fn ffprobe_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use crate::sidecar::{get_sidecar_path, SidecarType};
+
+    let ffmpeg = get_sidecar_path(app, SidecarType::Ffmpeg).map_err(|e| e.to_string())?;
+    let name = if cfg!(target_os = "windows") {
+        "ffprobe.exe"
+    } else {
+        "ffprobe"
+    };
+    let path = ffmpeg.with_file_name(name);
+    if !path.exists() {
+        return Err(format!("ffprobe not found: {}", path.to_string_lossy()));
+    }
+    Ok(path)
+}
+
+/// Duration of a media file in seconds, read with ffprobe
+#[cfg(not(target_os = "android"))]
+pub(crate) async fn probe_duration(app: &tauri::AppHandle, input: &Path) -> Result<f64, String> {
+    use tauri_plugin_shell::ShellExt;
+
+    let output = app
+        .shell()
+        .command(ffprobe_path(app)?.to_string_lossy().to_string())
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(input.to_string_lossy().to_string())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .map_err(|_| "Could not read media duration".to_string())
+}
+
+/// First `<stem><suffix>.<ext>` (or `... (n).<ext>`) next to `input` that is free
+#[cfg(not(target_os = "android"))]
+fn unique_output_path(input: &Path, suffix: &str, ext: &str) -> PathBuf {
+    let dir = input.parent().unwrap_or_else(|| Path::new("."));
+    let stem = input
+        .file_stem()
+        .map(|s| format!("{}{}", s.to_string_lossy(), suffix))
+        .unwrap_or_else(|| "output".to_string());
+
+    let mut candidate = dir.join(format!("{}.{}", stem, ext));
+    let mut n = 1;
+    while candidate.exists() {
+        candidate = dir.join(format!("{} ({}).{}", stem, n, ext));
+        n += 1;
+    }
+    candidate
+}
+
+/// ffmpeg encoder arguments for the target format
+///
+/// For MP3, `quality` is either a VBR level (`0`-`9`, lower is better) or a
+/// bitrate such as `320k`. FLAC is lossless, so `quality` is ignored.
+#[cfg(not(target_os = "android"))]
+fn encoder_args(format: AudioFormat, quality: Option<&str>) -> Result<Vec<String>, String> {
+    let args = match format {
+        AudioFormat::Mp3 => {
+            let quality_args = match quality.map(str::trim) {
+                None | Some("") => vec!["-q:a".to_string(), "0".to_string()],
+                Some(q) if q.ends_with('k') && q[..q.len() - 1].parse::<u32>().is_ok() => {
+                    vec!["-b:a".to_string(), q.to_string()]
+                }
+                Some(q) if q.parse::<u8>().is_ok_and(|level| level <= 9) => {
+                    vec!["-q:a".to_string(), q.to_string()]
+                }
+                Some(q) => return Err(format!("Invalid MP3 quality: {}", q)),
+            };
+
+            let mut args = vec!["-c:a".to_string(), "libmp3lame".to_string()];
+            args.extend(quality_args);
+            args.extend(["-id3v2_version".to_string(), "3".to_string()]);
+            args
+        }
+        AudioFormat::Flac => vec!["-c:a".to_string(), "flac".to_string()],
+    };
+
+    Ok(args)
+}
+
+/// Transcode `input` to `output`, keeping tags and cover art
+///
+/// A failed run removes the partial output.
+#[cfg(not(target_os = "android"))]
+pub(crate) async fn transcode_audio(
+    app: &tauri::AppHandle,
+    input: &Path,
+    output: &Path,
+    format: AudioFormat,
+    quality: Option<&str>,
+) -> Result<(), String> {
+    use crate::sidecar::{run_sidecar, SidecarType};
+
+    // Audio plus an optional attached picture; global tags carried over
+    let mut args = vec![
+        "-hide_banner".to_string(),
+        "-nostdin".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-map".to_string(),
+        "0:a:0".to_string(),
+        "-map".to_string(),
+        "0:v?".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+        "-c:v".to_string(),
+        "copy".to_string(),
+        "-disposition:v".to_string(),
+        "attached_pic".to_string(),
+    ];
+    args.extend(encoder_args(format, quality)?);
+    args.push(output.to_string_lossy().to_string());
+
+    let result = run_sidecar(app, SidecarType::Ffmpeg, &args, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !result.success() {
+        let _ = std::fs::remove_file(output);
+        let reason = result.stderr.lines().last().unwrap_or("unknown error").to_string();
+        return Err(format!("Conversion failed: {}", reason));
+    }
+
+    Ok(())
+}
+
+/// Decode the audio of `input` and measure its volume
+///
+/// Errors when ffmpeg can't decode the file or finds no audio stream.
+#[cfg(not(target_os = "android"))]
+pub(crate) async fn measure_audio_level(app: &tauri::AppHandle, input: &Path) -> Result<AudioLevel, String> {
+    use crate::sidecar::{run_sidecar, SidecarType};
+
+    let args = vec![
+        "-hide_banner".to_string(),
+        "-nostdin".to_string(),
+        "-nostats".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-vn".to_string(),
+        "-af".to_string(),
+        "volumedetect".to_string(),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ];
+
+    let result = run_sidecar(app, SidecarType::Ffmpeg, &args, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !result.success() {
+        let reason = result.stderr.lines().last().unwrap_or("unknown error").to_string();
+        return Err(format!("Audio could not be decoded: {}", reason));
+    }
+
+    // "[Parsed_volumedetect_0 @ 0x...] max_volume: -3.2 dB"
+    let read = |key: &str| {
+        result.stderr.lines().find_map(|line| {
+            let value = line.split(key).nth(1)?;
+            value.trim().trim_end_matches("dB").trim().parse::<f64>().ok()
+        })
+    };
+    let mean_volume_db = read("mean_volume:");
+    let max_volume_db = read("max_volume:");
+    if max_volume_db.is_none() {
+        return Err("No audio stream found".to_string());
+    }
+
+    Ok(AudioLevel {
+        mean_volume_db,
+        max_volume_db,
+        silent: max_volume_db.is_some_and(|db| db <= SILENCE_THRESHOLD_DB),
+    })
+}
+
+/// Convert a subtitle file to `format` next to the original
+///
+/// Returns `None` when the file already has that format.
+#[cfg(not(target_os = "android"))]
+pub(crate) async fn convert_subtitle(
+    app: &tauri::AppHandle,
+    input: &Path,
+    format: &str,
+) -> Result<Option<PathBuf>, String> {
+    use crate::sidecar::{run_sidecar, SidecarType};
+
+    if extension_of(input).as_deref() == Some(format) {
+        return Ok(None);
+    }
+
+    let output = input.with_extension(format);
+    let args = vec![
+        "-hide_banner".to_string(),
+        "-nostdin".to_string(),
+        "-y".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        output.to_string_lossy().to_string(),
+    ];
+
+    let result = run_sidecar(app, SidecarType::Ffmpeg, &args, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !result.success() {
+        let _ = std::fs::remove_file(&output);
+        return Err(format!(
+            "Subtitle conversion failed for {}",
+            input.to_string_lossy()
+        ));
+    }
+
+    Ok(Some(output))
+}
+
+/// Transcode a local audio file to another format, keeping tags and cover art
+///
+/// Returns the path of the new file, written next to the input.
+#[tauri::command]
+pub async fn convert_local_file(
+    input_path: String,
+    target_format: AudioFormat,
+    quality: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let input = PathBuf::from(&input_path);
+    if !input.is_file() {
+        return Err(format!("File not found: {}", input_path));
+    }
+
+    let ext = extension_of(&input).unwrap_or_default();
+    if !SUPPORTED_INPUT_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!("Unsupported audio container: {}", ext));
+    }
+    if ext == target_format.as_str() {
+        return Err(format!("File is already {}", target_format.as_str()));
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        let _ = (app, quality);
+        return Err("Local conversion is not supported on Android".to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let output = unique_output_path(&input, "", target_format.as_str());
+        transcode_audio(&app, &input, &output, target_format, quality.as_deref()).await?;
+
+        Ok(output.to_string_lossy().to_string())
+    }
+}
+
+/// Losslessly cut `start`..`end` out of a local file
+///
+/// Uses stream copy, so cut points snap to the nearest keyframes. Without an
+/// explicit `output`, the clip is written next to the input. Returns the
+/// clip's path.
+#[tauri::command]
+pub async fn trim_local_file(
+    input: String,
+    start: String,
+    end: String,
+    output: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let input_path = PathBuf::from(&input);
+    if !input_path.is_file() {
+        return Err(format!("File not found: {}", input));
+    }
+
+    let start_secs = parse_timestamp(&start).ok_or_else(|| format!("Invalid start time: {}", start))?;
+    let end_secs = parse_timestamp(&end).ok_or_else(|| format!("Invalid end time: {}", end))?;
+    if start_secs >= end_secs {
+        return Err("Start time must be before end time".to_string());
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        let _ = (output, app);
+        return Err("Local trimming is not supported on Android".to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use crate::sidecar::{run_sidecar, SidecarType};
+
+        let duration = probe_duration(&app, &input_path).await?;
+        if end_secs > duration {
+            return Err(format!(
+                "End time {:.1}s is past the end of the file ({:.1}s)",
+                end_secs, duration
+            ));
+        }
+
+        // Stream copy keeps the container, so the clip keeps the input's extension
+        let ext = extension_of(&input_path).unwrap_or_default();
+        let output_path = match output {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                if extension_of(&path).unwrap_or_default() != ext {
+                    return Err(format!("Output must keep the .{} extension", ext));
+                }
+                path
+            }
+            None => unique_output_path(&input_path, " (clip)", &ext),
+        };
+        if output_path == input_path {
+            return Err("Output must differ from the input file".to_string());
+        }
+
+        let args = vec![
+            "-hide_banner".to_string(),
+            "-nostdin".to_string(),
+            "-y".to_string(),
+            "-ss".to_string(),
+            format!("{:.3}", start_secs),
+            "-i".to_string(),
+            input_path.to_string_lossy().to_string(),
+            "-t".to_string(),
+            format!("{:.3}", end_secs - start_secs),
+            "-map".to_string(),
+            "0".to_string(),
+            "-c".to_string(),
+            "copy".to_string(),
+            output_path.to_string_lossy().to_string(),
+        ];
+
+        let result = run_sidecar(&app, SidecarType::Ffmpeg, &args, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !result.success() {
+            let _ = std::fs::remove_file(&output_path);
+            let reason = result.stderr.lines().last().unwrap_or("unknown error").to_string();
+            return Err(format!("Trim failed: {}", reason));
+        }
+
+        Ok(output_path.to_string_lossy().to_string())
+    }
+}
+
+/// Time converting a fixed sample to each audio format with the bundled ffmpeg
+///
+/// The sample is downloaded once and cached, so later runs measure only the
+/// CPU cost of the conversion.
+#[tauri::command]
+pub async fn benchmark_extraction(app: tauri::AppHandle) -> Result<ExtractionBenchmark, String> {
+    #[cfg(target_os = "android")]
+    {
+        let _ = app;
+        return Err("Benchmarking is not supported on Android".to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use tauri::Manager;
+
+        let bench_dir = app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| e.to_string())?
+            .join("benchmark");
+        std::fs::create_dir_all(&bench_dir).map_err(|e| e.to_string())?;
+
+        let sample = match find_benchmark_sample(&bench_dir) {
+            Some(sample) => sample,
+            None => fetch_benchmark_sample(&app, &bench_dir).await?,
+        };
+
+        let mut timings = Vec::with_capacity(AudioFormat::ALL.len());
+        for format in AudioFormat::ALL {
+            let output = bench_dir.join(format!("output.{}", format.as_str()));
+            let _ = std::fs::remove_file(&output);
+
+            let started = std::time::Instant::now();
+            transcode_audio(&app, &sample, &output, format, None).await?;
+            timings.push(FormatTiming {
+                format,
+                millis: started.elapsed().as_millis() as u64,
+            });
+            let _ = std::fs::remove_file(&output);
+        }
+
+        Ok(ExtractionBenchmark {
+            sample_duration_secs: probe_duration(&app, &sample).await.ok(),
+            timings,
+        })
+    }
+}
+
+/// Previously downloaded benchmark sample, if any
+#[cfg(not(target_os = "android"))]
+fn find_benchmark_sample(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_stem().is_some_and(|stem| stem == "sample")
+                && !matches!(extension_of(path).as_deref(), Some("part" | "ytdl"))
+        })
+}
+
+/// Download the benchmark sample's best audio stream into `dir`
+#[cfg(not(target_os = "android"))]
+async fn fetch_benchmark_sample(app: &tauri::AppHandle, dir: &Path) -> Result<PathBuf, String> {
+    use crate::sidecar::{run_sidecar, SidecarType};
+
+    let args = vec![
+        "--format".to_string(),
+        "bestaudio".to_string(),
+        "--no-playlist".to_string(),
+        "--output".to_string(),
+        dir.join("sample.%(ext)s").to_string_lossy().to_string(),
+        BENCHMARK_SAMPLE_URL.to_string(),
+    ];
+    let result = run_sidecar(
+        app,
+        SidecarType::YtDlp,
+        &args,
+        Some(std::time::Duration::from_secs(BENCHMARK_FETCH_TIMEOUT_SECS)),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !result.success() {
+        let reason = result.stderr.lines().last().unwrap_or("unknown error").to_string();
+        return Err(format!("Failed to download benchmark sample: {}", reason));
+    }
+    find_benchmark_sample(dir).ok_or_else(|| "Benchmark sample was not written".to_string())
+}
+
+/// Measure the volume of a local file to spot silent or undecodable audio
+#[tauri::command]
+pub async fn check_audio_level(input_path: String, app: tauri::AppHandle) -> Result<AudioLevel, String> {
+    let input = PathBuf::from(&input_path);
+    if !input.is_file() {
+        return Err(format!("File not found: {}", input_path));
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        let _ = app;
+        return Err("Audio analysis is not supported on Android".to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    measure_audio_level(&app, &input).await
+}
@@ -0,0 +1,169 @@
+//! Metadata enrichment commands
+//! Extra details derived from yt-dlp's info JSON
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::commands::download::{
+    fetch_info_json, run_ytdlp_json, sanitize_filename, validate_url, DownloadError,
+};
+use crate::state::AppState;
+
+/// Download `url` into `dest` unless it is already cached
+async fn cache_remote_file(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+) -> Result<(), DownloadError> {
+    if dest.exists() {
+        return Ok(());
+    }
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
+    }
+
+    let bytes = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?
+        .bytes()
+        .await
+        .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
+
+    std::fs::write(dest, &bytes).map_err(|e| DownloadError::DownloadFailed(e.to_string()))
+}
+
+/// Pick the avatar from a channel's thumbnail list
+fn find_avatar_url(channel: &serde_json::Value) -> Option<String> {
+    let thumbnails = channel.get("thumbnails")?.as_array()?;
+    let id_of = |t: &serde_json::Value| t.get("id").and_then(|id| id.as_str()).unwrap_or("").to_string();
+
+    // "avatar_uncropped" is the full-size image, other avatar ids are resized variants
+    thumbnails
+        .iter()
+        .find(|t| id_of(*t) == "avatar_uncropped")
+        .or_else(|| thumbnails.iter().rev().find(|t| id_of(*t).contains("avatar")))
+        .and_then(|t| t.get("url"))
+        .and_then(|u| u.as_str())
+        .map(|u| u.to_string())
+}
+
+/// Download the uploader's avatar into the cache dir and return its local path
+///
+/// Returns `None` when the video has no channel or the channel has no avatar.
+#[tauri::command]
+pub async fn get_channel_avatar(
+    url: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, DownloadError> {
+    validate_url(&url)?;
+    if state.network.is_stopped() {
+        return Err(DownloadError::NetworkStopped);
+    }
+
+    let info = fetch_info_json(&app, &url).await?;
+    let channel_url = match info
+        .get("channel_url")
+        .or_else(|| info.get("uploader_url"))
+        .and_then(|v| v.as_str())
+    {
+        Some(u) => u.to_string(),
+        None => return Ok(None),
+    };
+
+    let cache_key = info
+        .get("channel_id")
+        .and_then(|v| v.as_str())
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| sanitize_filename(&channel_url));
+    let avatar_path: PathBuf = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?
+        .join("avatars")
+        .join(format!("{}.jpg", cache_key));
+
+    if avatar_path.exists() {
+        return Ok(Some(avatar_path.to_string_lossy().to_string()));
+    }
+
+    // Channel metadata only - no entries are needed to get the thumbnails
+    let channel = run_ytdlp_json(
+        &app,
+        vec![
+            "--dump-single-json".to_string(),
+            "--flat-playlist".to_string(),
+            "--playlist-items".to_string(),
+            "0".to_string(),
+            channel_url,
+        ],
+    )
+    .await?;
+
+    let avatar_url = match find_avatar_url(&channel) {
+        Some(u) => u,
+        None => return Ok(None),
+    };
+
+    cache_remote_file(&state.http_client, &avatar_url, &avatar_path).await?;
+    Ok(Some(avatar_path.to_string_lossy().to_string()))
+}
+
+/// A chapter marker from the info JSON (times in seconds)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+/// List a video's chapters (empty when it has none)
+#[tauri::command]
+pub async fn list_chapters(url: String, app: tauri::AppHandle) -> Result<Vec<Chapter>, DownloadError> {
+    validate_url(&url)?;
+
+    let info = fetch_info_json(&app, &url).await?;
+    Ok(info
+        .get("chapters")
+        .cloned()
+        .and_then(|chapters| serde_json::from_value(chapters).ok())
+        .unwrap_or_default())
+}
+
+/// Which yt-dlp extractor handled a URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractorInfo {
+    /// Extractor name, e.g. `youtube` or `youtube:tab`
+    pub extractor: Option<String>,
+    /// Extractor class key, e.g. `Youtube` or `Generic`
+    pub extractor_key: Option<String>,
+    /// True when no site-specific extractor matched
+    pub is_generic: bool,
+}
+
+/// Report the extractor yt-dlp used for a URL
+#[tauri::command]
+pub async fn get_extractor_info(url: String, app: tauri::AppHandle) -> Result<ExtractorInfo, DownloadError> {
+    validate_url(&url)?;
+
+    let info = fetch_info_json(&app, &url).await?;
+    let field = |key: &str| info.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    let extractor = field("extractor");
+    let extractor_key = field("extractor_key");
+    let is_generic = extractor_key
+        .as_deref()
+        .or(extractor.as_deref())
+        .is_some_and(|key| key.eq_ignore_ascii_case("generic"));
+
+    Ok(ExtractorInfo {
+        extractor,
+        extractor_key,
+        is_generic,
+    })
+}
@@ -1,7 +1,17 @@
 //! Tauri commands module
 
+pub mod diagnostics;
 pub mod download;
+pub mod history;
+pub mod media;
+pub mod metadata;
+pub mod queue;
 pub mod setup;
 
+pub use diagnostics::*;
 pub use download::*;
+pub use history::*;
+pub use media::*;
+pub use metadata::*;
+pub use queue::*;
 pub use setup::*;
@@ -0,0 +1,439 @@
+//! Download queue commands
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::commands::download::{
+    new_download_id, validate_url, DownloadFormat, DownloadError, OptionOverrides,
+};
+use crate::queue::batch::{self, Batch, BatchItem, BatchSummary, ItemStatus};
+use crate::queue::{self, JobStatus, QueuedJob};
+use crate::state::AppState;
+
+/// Payload for `download-removed` events
+#[derive(Clone, Serialize)]
+struct RemovedPayload {
+    id: String,
+}
+
+/// Add a download to the queue and return its job id
+#[tauri::command]
+pub async fn enqueue_download(
+    url: String,
+    format: DownloadFormat,
+    options: Option<OptionOverrides>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, DownloadError> {
+    validate_url(&url)?;
+    let options = crate::profiles::resolve_options(&app, &url, &options.unwrap_or_default())?;
+
+    let id = new_download_id();
+    {
+        let mut jobs = state.queue.jobs.lock().await;
+        jobs.push_back(QueuedJob {
+            id: id.clone(),
+            url,
+            format,
+            options,
+            batch_id: None,
+            status: JobStatus::Pending,
+            error: None,
+        });
+        let _ = queue::save_queue(&app, &jobs);
+    }
+
+    queue::start_worker(app);
+    Ok(id)
+}
+
+/// Get every job currently in the queue
+#[tauri::command]
+pub async fn get_download_queue(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<QueuedJob>, String> {
+    Ok(state.queue.jobs.lock().await.iter().cloned().collect())
+}
+
+/// Pause the queue; running downloads finish but no new ones start
+#[tauri::command]
+pub fn pause_queue(state: tauri::State<'_, AppState>) {
+    state.power_pause.forget();
+    state.queue.set_paused(true);
+}
+
+/// Resume the queue, e.g. after it was restored paused at launch
+#[tauri::command]
+pub fn resume_queue(app: tauri::AppHandle, state: tauri::State<'_, AppState>) {
+    state.power_pause.forget();
+    state.queue.set_paused(false);
+    queue::start_worker(app);
+}
+
+/// Whether the queue pauses on battery and on metered connections
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PowerPauseSettings {
+    pub pause_on_battery: bool,
+    pub pause_on_metered: bool,
+}
+
+/// Get the automatic pause settings
+#[tauri::command]
+pub fn get_power_pause_settings(app: tauri::AppHandle) -> PowerPauseSettings {
+    PowerPauseSettings {
+        pause_on_battery: crate::settings::get_bool(&app, crate::power::PAUSE_ON_BATTERY, false),
+        pause_on_metered: crate::settings::get_bool(&app, crate::power::PAUSE_ON_METERED, false),
+    }
+}
+
+/// Pause the queue automatically on battery and/or metered connections
+///
+/// Takes effect at the next check, within half a minute.
+#[tauri::command]
+pub fn set_power_pause_settings(
+    settings: PowerPauseSettings,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    crate::settings::set_value(
+        &app,
+        crate::power::PAUSE_ON_BATTERY,
+        serde_json::json!(settings.pause_on_battery),
+    )?;
+    crate::settings::set_value(
+        &app,
+        crate::power::PAUSE_ON_METERED,
+        serde_json::json!(settings.pause_on_metered),
+    )
+}
+
+/// Detect whether the machine is on battery or a metered connection
+#[tauri::command]
+pub async fn get_power_status() -> Result<crate::power::PowerStatus, String> {
+    Ok(crate::power::detect().await)
+}
+
+/// Queue export file format version
+const QUEUE_EXPORT_VERSION: u32 = 1;
+
+/// A queued download as written to an export file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedJob {
+    pub url: String,
+    pub format: DownloadFormat,
+    /// Options sent to the import are layered over the URL's site profile
+    #[serde(default)]
+    pub options: OptionOverrides,
+}
+
+/// Outcome of a queue import
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueImport {
+    pub imported: usize,
+    /// Why each rejected entry was skipped, by its position in the file
+    pub skipped: Vec<String>,
+}
+
+/// Write the pending and running jobs to a JSON file
+///
+/// Failed jobs are left out. Returns the number of jobs written.
+#[tauri::command]
+pub async fn export_queue(path: String, state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let jobs: Vec<ExportedJob> = state
+        .queue
+        .jobs
+        .lock()
+        .await
+        .iter()
+        .filter(|job| job.status != JobStatus::Failed)
+        .map(|job| ExportedJob {
+            url: job.url.clone(),
+            format: job.format,
+            options: OptionOverrides::from_options(&job.options),
+        })
+        .collect();
+
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "version": QUEUE_EXPORT_VERSION,
+        "jobs": jobs,
+    }))
+    .map_err(|e| format!("Serialization error: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(jobs.len())
+}
+
+/// Append the jobs of an exported queue file to the queue
+///
+/// Entries that don't parse or have an invalid URL are skipped, not fatal.
+#[tauri::command]
+pub async fn import_queue(
+    path: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<QueueImport, String> {
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Not a queue export: {}", e))?;
+    let entries = value
+        .get("jobs")
+        .and_then(|jobs| jobs.as_array())
+        .ok_or_else(|| "Not a queue export: missing \"jobs\" list".to_string())?;
+
+    let mut skipped = Vec::new();
+    let mut imported = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let job = match serde_json::from_value::<ExportedJob>(entry.clone()) {
+            Ok(job) => job,
+            Err(e) => {
+                skipped.push(format!("Entry {}: {}", i + 1, e));
+                continue;
+            }
+        };
+        let options = match validate_url(&job.url)
+            .and_then(|_| crate::profiles::resolve_options(&app, &job.url, &job.options))
+        {
+            Ok(options) => options,
+            Err(e) => {
+                skipped.push(format!("Entry {}: {}", i + 1, e));
+                continue;
+            }
+        };
+        imported.push(QueuedJob {
+            id: new_download_id(),
+            url: job.url,
+            format: job.format,
+            options,
+            batch_id: None,
+            status: JobStatus::Pending,
+            error: None,
+        });
+    }
+
+    let count = imported.len();
+    if count > 0 {
+        let mut jobs = state.queue.jobs.lock().await;
+        jobs.extend(imported);
+        queue::save_queue(&app, &jobs)?;
+        drop(jobs);
+        queue::start_worker(app);
+    }
+
+    Ok(QueueImport {
+        imported: count,
+        skipped,
+    })
+}
+
+/// Remove a job that has not started yet (pending or failed) from the queue
+#[tauri::command]
+pub async fn remove_queued_download(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut jobs = state.queue.jobs.lock().await;
+    let index = jobs
+        .iter()
+        .position(|job| job.id == id)
+        .ok_or_else(|| format!("No queued download with id {}", id))?;
+
+    if jobs[index].status == JobStatus::Running {
+        return Err("Download is already running; cancel it instead".to_string());
+    }
+
+    jobs.remove(index);
+    queue::save_queue(&app, &jobs)?;
+    drop(jobs);
+
+    let _ = app.emit("download-removed", RemovedPayload { id });
+    Ok(())
+}
+
+/// Put a failed job back in line
+#[tauri::command]
+pub async fn retry_queued_download(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut batches = state.queue.batches.lock().await;
+    let mut jobs = state.queue.jobs.lock().await;
+    let job = jobs
+        .iter_mut()
+        .find(|job| job.id == id)
+        .ok_or_else(|| format!("No queued download with id {}", id))?;
+
+    if job.status != JobStatus::Failed {
+        return Err("Only failed downloads can be retried".to_string());
+    }
+    job.status = JobStatus::Pending;
+    job.error = None;
+
+    // Keep the batch summary in step with the job
+    if let Some(batch) = job.batch_id.as_ref().and_then(|b| batches.get_mut(b)) {
+        if let Some(item) = batch.items.iter_mut().find(|item| item.job_id == id) {
+            item.status = ItemStatus::Pending;
+            item.error = None;
+        }
+        batch::save_batches(&app, &batches)?;
+    }
+    queue::save_queue(&app, &jobs)?;
+    drop(jobs);
+    drop(batches);
+
+    queue::start_worker(app);
+    Ok(())
+}
+
+/// Get how many queued downloads run at once
+#[tauri::command]
+pub fn get_max_concurrent_downloads(app: tauri::AppHandle) -> usize {
+    queue::max_concurrent(&app)
+}
+
+/// Set how many queued downloads run at once (1 to `queue::MAX_CONCURRENT`)
+#[tauri::command]
+pub fn set_max_concurrent_downloads(limit: u64, app: tauri::AppHandle) -> Result<(), String> {
+    if !(1..=queue::MAX_CONCURRENT).contains(&limit) {
+        return Err(format!(
+            "Limit must be between 1 and {}",
+            queue::MAX_CONCURRENT
+        ));
+    }
+    crate::settings::set_value(
+        &app,
+        crate::settings::MAX_CONCURRENT_DOWNLOADS,
+        serde_json::json!(limit),
+    )?;
+
+    // Raising the limit starts waiting jobs right away
+    queue::start_worker(app);
+    Ok(())
+}
+
+/// Rough per-item download time (short for a song, long for a long video)
+const ESTIMATED_DOWNLOAD_SECS: (u64, u64) = (10, 60);
+
+/// Expected wall-clock time for a batch, in seconds
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEstimate {
+    pub min_secs: u64,
+    pub max_secs: u64,
+    /// Time spent deliberately waiting between downloads, at the average delay
+    pub delay_secs: u64,
+    pub concurrency: usize,
+}
+
+/// Estimate how long `count` downloads take with the current anti-ban delays
+/// and concurrency limit
+///
+/// Each running slot waits its random delay before every download, so a
+/// batch runs in `count / concurrency` rounds of delay plus download.
+#[tauri::command]
+pub fn estimate_batch_duration(count: u32, app: tauri::AppHandle) -> BatchEstimate {
+    let config = crate::anti_ban::load_config(&app);
+    let concurrency = queue::max_concurrent(&app).max(1);
+    let rounds = (count as u64).div_ceil(concurrency as u64);
+
+    let (min_delay, max_delay) = if config.enable_delays && config.min_delay_secs > 0 {
+        (config.min_delay_secs, config.max_delay_secs.max(config.min_delay_secs))
+    } else {
+        (0, 0)
+    };
+    let (min_download, max_download) = ESTIMATED_DOWNLOAD_SECS;
+
+    BatchEstimate {
+        min_secs: rounds * (min_delay + min_download),
+        max_secs: rounds * (max_delay + max_download),
+        delay_secs: rounds * (min_delay + max_delay) / 2,
+        concurrency,
+    }
+}
+
+/// Enqueue several URLs as one batch and return the batch id
+///
+/// Invalid URLs are recorded as failed items instead of rejecting the batch.
+#[tauri::command]
+pub async fn enqueue_batch(
+    urls: Vec<String>,
+    format: DownloadFormat,
+    options: Option<OptionOverrides>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    options.to_options().map_err(|e| e.to_string())?;
+    let batch_id = new_download_id();
+    let mut items = Vec::with_capacity(urls.len());
+
+    {
+        let mut batches = state.queue.batches.lock().await;
+        let mut jobs = state.queue.jobs.lock().await;
+        for url in urls {
+            let job_id = new_download_id();
+            // Each URL gets the options layered over its own site profile
+            let merged = validate_url(&url)
+                .and_then(|_| crate::profiles::resolve_options(&app, &url, &options))
+                .map_err(|e| e.to_string());
+            let error = merged.as_ref().err().cloned();
+            if let Ok(merged) = merged {
+                jobs.push_back(QueuedJob {
+                    id: job_id.clone(),
+                    url: url.clone(),
+                    format,
+                    options: merged,
+                    batch_id: Some(batch_id.clone()),
+                    status: JobStatus::Pending,
+                    error: None,
+                });
+            }
+            items.push(BatchItem {
+                job_id,
+                url,
+                status: if error.is_some() {
+                    ItemStatus::Failed
+                } else {
+                    ItemStatus::Pending
+                },
+                error,
+            });
+        }
+
+        batches.insert(
+            batch_id.clone(),
+            Batch {
+                id: batch_id.clone(),
+                format,
+                options,
+                items,
+            },
+        );
+        queue::save_queue(&app, &jobs)?;
+        batch::save_batches(&app, &batches)?;
+    }
+
+    queue::start_worker(app);
+    Ok(batch_id)
+}
+
+/// Get the per-item results of a batch
+#[tauri::command]
+pub async fn get_batch_summary(
+    batch_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<BatchSummary, String> {
+    state
+        .queue
+        .batches
+        .lock()
+        .await
+        .get(&batch_id)
+        .map(Batch::summary)
+        .ok_or_else(|| format!("Unknown batch: {}", batch_id))
+}
+
+/// Re-queue only the items of a batch that failed, returning how many
+#[tauri::command]
+pub async fn retry_failed_items(batch_id: String, app: tauri::AppHandle) -> Result<usize, String> {
+    batch::retry_failed(&app, &batch_id).await
+}
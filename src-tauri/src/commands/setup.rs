@@ -31,6 +31,8 @@ pub async fn install_sidecar(
     app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
+    state.network.ensure_allowed()?;
+
     // Download yt-dlp first
     manager::download_binary(&app, SidecarType::YtDlp, &state.http_client)
         .await
@@ -43,3 +45,351 @@ pub async fn install_sidecar(
     
     Ok(())
 }
+
+/// Get the version of the installed yt-dlp
+///
+/// Desktop runs the sidecar with `--version`; Android asks the plugin.
+#[tauri::command]
+pub async fn get_ytdlp_version(app: tauri::AppHandle) -> Result<String, String> {
+    #[cfg(target_os = "android")]
+    {
+        use tauri_plugin_ytdlp::YtdlpExt;
+        return app
+            .ytdlp()
+            .get_version()
+            .map(|v| v.version.trim().to_string())
+            .map_err(|e| e.to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let output = manager::run_sidecar(
+            &app,
+            SidecarType::YtDlp,
+            &["--version".to_string()],
+            Some(std::time::Duration::from_secs(15)),
+        )
+        .await
+        .map_err(|e| match e {
+            manager::SidecarError::NotFound(_) => {
+                "yt-dlp is not installed; install it from the setup screen".to_string()
+            }
+            e => e.to_string(),
+        })?;
+
+        if !output.success() {
+            return Err(format!("yt-dlp exited with code {:?}", output.code));
+        }
+
+        output
+            .stdout
+            .lines()
+            .next()
+            .map(|l| l.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| "Could not read yt-dlp version".to_string())
+    }
+}
+
+/// Replace the installed yt-dlp with the latest release
+///
+/// Re-downloads the binary rather than using `yt-dlp -U`, which refuses to
+/// update some builds and can't report progress. Progress goes out on
+/// `setup-progress` like a fresh install; returns the new version.
+#[tauri::command]
+pub async fn update_sidecar(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    #[cfg(target_os = "android")]
+    {
+        let _ = (app, state);
+        return Err("yt-dlp is updated through the Android plugin".to_string());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        Ok(refresh_ytdlp(&app, &state).await?.version)
+    }
+}
+
+/// Outcome of a yt-dlp update
+#[derive(Debug, Clone, Serialize)]
+pub struct YtDlpUpdate {
+    /// False when the latest release was already installed
+    pub updated: bool,
+    /// Version before the update, if yt-dlp was installed and runnable
+    pub previous: Option<String>,
+    pub version: String,
+}
+
+/// Update yt-dlp and report whether the version changed
+///
+/// Desktop re-downloads the sidecar like `update_sidecar`; Android asks the
+/// plugin to update its bundled copy.
+#[tauri::command]
+pub async fn update_ytdlp(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<YtDlpUpdate, String> {
+    state.network.ensure_allowed()?;
+
+    #[cfg(target_os = "android")]
+    {
+        use tauri_plugin_ytdlp::YtdlpExt;
+
+        let previous = app.ytdlp().get_version().ok().map(|v| v.version);
+        let response = app
+            .ytdlp()
+            .update_ytdlp(tauri_plugin_ytdlp::models::UpdateRequest { channel: None })
+            .map_err(|e| e.to_string())?;
+        let version = app
+            .ytdlp()
+            .get_version()
+            .map(|v| v.version)
+            .unwrap_or(response.status);
+        return Ok(YtDlpUpdate {
+            updated: response.updated,
+            previous,
+            version,
+        });
+    }
+
+    #[cfg(not(target_os = "android"))]
+    refresh_ytdlp(&app, &state).await
+}
+
+/// Re-download the yt-dlp sidecar and announce the result on `setup-progress`
+#[cfg(not(target_os = "android"))]
+async fn refresh_ytdlp(app: &tauri::AppHandle, state: &AppState) -> Result<YtDlpUpdate, String> {
+    use tauri::Emitter;
+
+    state.network.ensure_allowed()?;
+    let previous = get_ytdlp_version(app.clone()).await.ok();
+
+    manager::download_binary(app, SidecarType::YtDlp, &state.http_client)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let version = get_ytdlp_version(app.clone()).await?;
+    let status = match &previous {
+        Some(previous) if *previous == version => format!("yt-dlp {} is already the latest", version),
+        Some(previous) => format!("Updated yt-dlp from {} to {}", previous, version),
+        None => format!("Installed yt-dlp {}", version),
+    };
+    let _ = app.emit("setup-progress", serde_json::json!({
+        "type": SidecarType::YtDlp,
+        "progress": 100.0,
+        "status": status
+    }));
+
+    Ok(YtDlpUpdate {
+        updated: previous.as_ref() != Some(&version),
+        previous,
+        version,
+    })
+}
+
+/// Latest upstream ffmpeg release, as published by the macOS build mirror
+const FFMPEG_RELEASE_INFO_URL: &str = "https://evermeet.cx/ffmpeg/info/ffmpeg/release";
+
+/// Installed ffmpeg compared with the latest release
+#[derive(Debug, Clone, Serialize)]
+pub struct FfmpegUpdateInfo {
+    pub installed: String,
+    pub latest: String,
+    /// `None` for git snapshot builds, whose versions can't be compared to releases
+    pub update_available: Option<bool>,
+}
+
+/// Extract the version from the first line of `ffmpeg -version`
+fn parse_ffmpeg_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip_while(|word| *word != "version")
+        .nth(1)
+        .map(|v| v.to_string())
+}
+
+/// Numeric release components of a version like `7.1.1-essentials` or `n6.0`
+fn release_number(version: &str) -> Option<Vec<u32>> {
+    let version = version.strip_prefix('n').unwrap_or(version);
+    let numeric: String = version
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+
+    let parts: Vec<u32> = numeric
+        .split('.')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts)
+    }
+}
+
+/// Get the version of the installed ffmpeg
+#[tauri::command]
+pub async fn get_ffmpeg_version(app: tauri::AppHandle) -> Result<String, String> {
+    let output = manager::run_sidecar(
+        &app,
+        SidecarType::Ffmpeg,
+        &["-version".to_string()],
+        Some(std::time::Duration::from_secs(10)),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !output.success() {
+        return Err(format!("ffmpeg exited with code {:?}", output.code));
+    }
+
+    parse_ffmpeg_version(&output.stdout).ok_or_else(|| "Could not parse ffmpeg version".to_string())
+}
+
+/// Compare the installed ffmpeg with the latest upstream release
+///
+/// Updating stays manual (reinstalling via `install_sidecar`); this only
+/// reports whether it is worth doing.
+#[tauri::command]
+pub async fn check_ffmpeg_update(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<FfmpegUpdateInfo, String> {
+    state.network.ensure_allowed()?;
+    let installed = get_ffmpeg_version(app).await?;
+
+    let info: serde_json::Value = state
+        .http_client
+        .get(FFMPEG_RELEASE_INFO_URL)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch latest ffmpeg release: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release info: {}", e))?;
+
+    let latest = info
+        .get("version")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Release info has no version".to_string())?
+        .to_string();
+
+    let update_available = match (release_number(&installed), release_number(&latest)) {
+        (Some(current), Some(newest)) => Some(current < newest),
+        _ => None,
+    };
+
+    Ok(FfmpegUpdateInfo {
+        installed,
+        latest,
+        update_available,
+    })
+}
+
+/// Result of actually launching a sidecar binary
+#[derive(Debug, Clone, Serialize)]
+pub struct SidecarExecTest {
+    pub ok: bool,
+    /// First line of the version output when the launch succeeded
+    pub version: Option<String>,
+    pub error: Option<String>,
+    /// Suggested fix for the OS-level error
+    pub hint: Option<String>,
+}
+
+/// Map a spawn error to a remediation hint
+fn spawn_error_hint(error: &str, path: &str) -> Option<String> {
+    let lower = error.to_lowercase();
+
+    if lower.contains("not found") || lower.contains("no such file") {
+        return Some("The binary is missing. Reinstall it from the setup screen.".to_string());
+    }
+    if lower.contains("virus") || lower.contains("os error 225") {
+        return Some("Antivirus blocked the binary. Add an exception for it and reinstall.".to_string());
+    }
+    if lower.contains("permission denied")
+        || lower.contains("operation not permitted")
+        || lower.contains("access is denied")
+        || lower.contains("os error 5")
+        || lower.contains("os error 13")
+    {
+        #[cfg(target_os = "macos")]
+        return Some(format!(
+            "Gatekeeper is blocking the binary. Run: xattr -d com.apple.quarantine \"{}\"",
+            path
+        ));
+        #[cfg(target_os = "windows")]
+        return Some(format!(
+            "SmartScreen or antivirus is blocking the binary. Allow \"{}\" and try again.",
+            path
+        ));
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        return Some(format!("The binary is not executable. Run: chmod +x \"{}\"", path));
+    }
+    if lower.contains("exec format error") || lower.contains("bad cpu type") {
+        return Some("The binary was built for another CPU architecture. Reinstall it.".to_string());
+    }
+    None
+}
+
+/// Try to launch a sidecar with its version flag to prove the OS lets it run
+#[tauri::command]
+pub async fn test_sidecar_executable(
+    sidecar_type: SidecarType,
+    app: tauri::AppHandle,
+) -> SidecarExecTest {
+    let path = manager::get_sidecar_path(&app, sidecar_type)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let version_flag = match sidecar_type {
+        SidecarType::YtDlp => "--version",
+        SidecarType::Ffmpeg => "-version",
+    };
+
+    let result = manager::run_sidecar(
+        &app,
+        sidecar_type,
+        &[version_flag.to_string()],
+        Some(std::time::Duration::from_secs(15)),
+    )
+    .await;
+
+    match result {
+        Ok(output) if output.success() => SidecarExecTest {
+            ok: true,
+            version: output.stdout.lines().next().map(|l| l.trim().to_string()),
+            error: None,
+            hint: None,
+        },
+        Ok(output) => {
+            let error = format!(
+                "Exited with code {:?}: {}",
+                output.code,
+                output.stderr.lines().last().unwrap_or("").trim()
+            );
+            SidecarExecTest {
+                ok: false,
+                version: None,
+                hint: spawn_error_hint(&error, &path),
+                error: Some(error),
+            }
+        }
+        Err(e) => {
+            let error = e.to_string();
+            SidecarExecTest {
+                ok: false,
+                version: None,
+                hint: spawn_error_hint(&error, &path),
+                error: Some(error),
+            }
+        }
+    }
+}
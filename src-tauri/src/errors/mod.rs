@@ -0,0 +1,71 @@
+//! Recent download errors
+//! Bounded history of failures, grouped by error category
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Errors kept in memory
+const CAPACITY: usize = 200;
+
+/// One failed download
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorRecord {
+    pub timestamp: String,
+    pub category: String,
+    pub message: String,
+    pub url: String,
+}
+
+/// Recent errors with per-category counts
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentErrors {
+    pub errors: Vec<ErrorRecord>,
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Ring buffer of recent errors, oldest first
+#[derive(Default)]
+pub struct ErrorHistory {
+    entries: Mutex<VecDeque<(chrono::DateTime<chrono::Local>, ErrorRecord)>>,
+}
+
+impl ErrorHistory {
+    /// Record a failure, dropping the oldest when full
+    pub fn record(&self, category: &str, message: String, url: &str) {
+        let now = chrono::Local::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        while entries.len() >= CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back((
+            now,
+            ErrorRecord {
+                timestamp: now.to_rfc3339(),
+                category: category.to_string(),
+                message,
+                url: url.to_string(),
+            },
+        ));
+    }
+
+    /// Errors from the last `within_secs` seconds (all kept errors when None)
+    pub fn recent(&self, within_secs: Option<u64>) -> RecentErrors {
+        let cutoff = within_secs.map(|secs| {
+            chrono::Local::now() - chrono::Duration::seconds(secs.min(u32::MAX as u64) as i64)
+        });
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        let errors: Vec<ErrorRecord> = entries
+            .iter()
+            .filter(|(at, _)| cutoff.is_none_or(|cutoff| *at >= cutoff))
+            .map(|(_, record)| record.clone())
+            .collect();
+        let mut counts = BTreeMap::new();
+        for record in &errors {
+            *counts.entry(record.category.clone()).or_insert(0) += 1;
+        }
+
+        RecentErrors { errors, counts }
+    }
+}
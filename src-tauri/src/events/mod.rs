@@ -0,0 +1,126 @@
+//! Event throttling
+//! Coalesces high-frequency events so large batches don't flood the webview
+
+use serde::Serialize;
+use std::collections::hash_map::{Entry, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::state::AppState;
+
+/// Default minimum gap between two events of the same stream
+pub const DEFAULT_INTERVAL_MS: u64 = 100;
+
+/// Upper bound for the configurable interval, beyond which progress looks frozen
+pub const MAX_INTERVAL_MS: u64 = 2000;
+
+/// Throttling state for one event stream (event name + download id)
+struct Slot {
+    last_emit: Instant,
+    /// Latest payload held back until the interval has passed
+    pending: Option<serde_json::Value>,
+    flush_scheduled: bool,
+}
+
+/// Coalesces events per stream, keeping only the latest payload
+#[derive(Default)]
+pub struct EventThrottle {
+    slots: Mutex<HashMap<String, Slot>>,
+}
+
+impl EventThrottle {
+    /// Emit `payload` at most once per `interval` for this `event`/`id` pair
+    ///
+    /// Payloads arriving too soon replace each other and the latest one is
+    /// emitted once the interval has passed.
+    pub fn emit<R: tauri::Runtime, S: Serialize>(
+        &self,
+        app: &tauri::AppHandle<R>,
+        event: &'static str,
+        id: &str,
+        payload: S,
+        interval: Duration,
+    ) {
+        let key = format!("{}:{}", event, id);
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+
+        let slot = match slots.entry(key.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                // First event of a stream always goes out immediately
+                entry.insert(Slot {
+                    last_emit: Instant::now(),
+                    pending: None,
+                    flush_scheduled: false,
+                });
+                let _ = app.emit(event, payload);
+                return;
+            }
+        };
+
+        let elapsed = slot.last_emit.elapsed();
+        if elapsed >= interval && !slot.flush_scheduled {
+            slot.last_emit = Instant::now();
+            let _ = app.emit(event, payload);
+            return;
+        }
+
+        slot.pending = serde_json::to_value(payload).ok();
+        if slot.flush_scheduled {
+            return;
+        }
+        slot.flush_scheduled = true;
+
+        let app = app.clone();
+        let delay = interval.saturating_sub(elapsed);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(delay).await;
+            app.state::<AppState>()
+                .event_throttle
+                .flush(&app, event, &key);
+        });
+    }
+
+    /// Emit the held-back payload for a stream, if it is still tracked
+    fn flush<R: tauri::Runtime>(&self, app: &tauri::AppHandle<R>, event: &'static str, key: &str) {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(slot) = slots.get_mut(key) {
+            slot.flush_scheduled = false;
+            if let Some(payload) = slot.pending.take() {
+                slot.last_emit = Instant::now();
+                let _ = app.emit(event, payload);
+            }
+        }
+    }
+
+    /// Emit a final event immediately and stop tracking the stream
+    ///
+    /// Any held-back payload is dropped so it can't arrive after this one.
+    pub fn emit_final<R: tauri::Runtime, S: Serialize>(
+        &self,
+        app: &tauri::AppHandle<R>,
+        event: &'static str,
+        id: &str,
+        payload: S,
+    ) {
+        self.forget(event, id);
+        let _ = app.emit(event, payload);
+    }
+
+    /// Stop tracking a stream, discarding any held-back payload
+    pub fn forget(&self, event: &'static str, id: &str) {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        slots.remove(&format!("{}:{}", event, id));
+    }
+}
+
+/// Configured minimum gap between progress events of one download
+pub fn progress_interval<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Duration {
+    let ms = crate::settings::get_u64(
+        app,
+        crate::settings::PROGRESS_EVENT_INTERVAL_MS,
+        DEFAULT_INTERVAL_MS,
+    );
+    Duration::from_millis(ms.min(MAX_INTERVAL_MS))
+}
@@ -0,0 +1,81 @@
+//! Custom HTTP headers
+//! User-managed headers passed to every yt-dlp request
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Settings key holding the header list
+const HEADERS_KEY: &str = "customHeaders";
+
+/// RFC 7230 token characters allowed in a header name
+static HEADER_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$").expect("Invalid header name regex")
+});
+
+/// A header added to requests, e.g. an API key a site expects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// Reject names that aren't HTTP tokens and values that could inject lines
+pub fn validate_headers(headers: &[CustomHeader]) -> Result<(), String> {
+    for header in headers {
+        if !HEADER_NAME_REGEX.is_match(&header.name) {
+            return Err(format!("Invalid header name: {:?}", header.name));
+        }
+        if header.value.contains(['\r', '\n']) {
+            return Err(format!("Header {} has a line break in its value", header.name));
+        }
+    }
+    Ok(())
+}
+
+/// Load the header list from the settings store
+pub fn load_headers<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<CustomHeader> {
+    use tauri_plugin_store::StoreExt;
+
+    app.store(crate::settings::STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(HEADERS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the header list
+pub fn save_headers<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    headers: &[CustomHeader],
+) -> Result<(), String> {
+    validate_headers(headers)?;
+    crate::settings::set_value(
+        app,
+        HEADERS_KEY,
+        serde_json::to_value(headers).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
+/// `--add-header` arguments for every saved header
+pub fn to_ytdlp_args<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<String> {
+    load_headers(app)
+        .into_iter()
+        .flat_map(|header| {
+            [
+                "--add-header".to_string(),
+                format!("{}:{}", header.name, header.value),
+            ]
+        })
+        .collect()
+}
+
+/// Header names for logging; values are never logged since they often hold secrets
+pub fn describe<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Option<String> {
+    let names: Vec<String> = load_headers(app).into_iter().map(|h| h.name).collect();
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(", "))
+    }
+}
@@ -0,0 +1,86 @@
+//! Download history
+//! Keeps track of what was downloaded during the current app session, plus a
+//! persisted log of completed downloads across sessions
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::download::{DownloadFormat, DownloadResult};
+
+pub(crate) const STORE_PATH: &str = "history.json";
+
+/// Oldest entries are dropped beyond this many
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Output paths of downloads completed since launch, oldest first
+#[derive(Default)]
+pub struct SessionDownloads {
+    paths: Mutex<Vec<String>>,
+}
+
+impl SessionDownloads {
+    /// Record a completed download's output path
+    pub fn record(&self, path: &str) {
+        if path.is_empty() {
+            return;
+        }
+        let mut paths = self.paths.lock().unwrap_or_else(|e| e.into_inner());
+        paths.push(path.to_string());
+    }
+
+    /// Snapshot of the recorded paths
+    pub fn paths(&self) -> Vec<String> {
+        self.paths.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// A completed download, kept so it can be found and fetched again later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub format: DownloadFormat,
+    /// RFC 3339 local time the download finished
+    pub completed_at: String,
+    pub result: DownloadResult,
+}
+
+/// Load the persisted history, oldest first
+pub fn load_history<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<HistoryEntry> {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get("history"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Append a completed download to the persisted history
+pub async fn record_download<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &str,
+    format: DownloadFormat,
+    result: &DownloadResult,
+) -> Result<(), String> {
+    crate::settings::update_store(app, STORE_PATH, "history", |entries: &mut Vec<HistoryEntry>| {
+        entries.push(HistoryEntry {
+            url: url.to_string(),
+            format,
+            completed_at: chrono::Local::now().to_rfc3339(),
+            result: result.clone(),
+        });
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Forget every recorded download
+pub async fn clear_history<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    crate::settings::update_store(app, STORE_PATH, "history", |entries: &mut Vec<HistoryEntry>| {
+        entries.clear();
+        Ok(())
+    })
+    .await
+}
@@ -2,11 +2,28 @@
 //! Ultra-lightweight desktop tool with Retro-Terminal Cute aesthetic
 
 mod anti_ban;
+mod archive;
+mod backoff;
+mod cache;
+mod cancel;
 mod commands;
+mod errors;
+mod events;
+mod headers;
+mod history;
+mod logs;
+mod network;
+mod playlist;
+mod power;
+mod profiles;
 mod proxy;
+mod queue;
 mod safety;
+mod settings;
 mod sidecar;
+mod sponsorblock;
 mod state;
+mod storage;
 
 use state::AppState;
 use tauri::Manager;
@@ -21,26 +38,128 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         // yt-dlp plugin for Android (uses youtubedl-android library)
         .plugin(tauri_plugin_ytdlp::init())
-        // Register shared app state (HTTP client with connection pooling)
-        .manage(AppState::new())
         // Register commands
         .invoke_handler(tauri::generate_handler![
             commands::start_download,
+            commands::cancel_download,
+            commands::resume_playlist,
+            commands::get_download_retries,
+            commands::set_download_retries,
+            commands::get_resume_downloads,
+            commands::set_resume_downloads,
             commands::get_video_info,
             commands::get_download_count,
             commands::set_gate_bypass,
+            commands::check_clock_skew,
+            commands::repair_safety_gate,
+            commands::get_safety_config,
+            commands::set_safety_config,
             commands::get_proxy_config,
             commands::set_proxy_config,
+            commands::get_proxy_env_fallback,
+            commands::set_proxy_env_fallback,
             commands::import_proxies,
+            commands::get_proxy_pool,
+            commands::set_proxy_pool,
+            commands::prune_proxy_pool,
+            commands::get_proxy_rotation,
+            commands::set_proxy_rotation,
             commands::get_anti_ban_config,
             commands::set_anti_ban_config,
+            commands::activate_anti_ban_profile,
             commands::check_sidecar_status,
             commands::install_sidecar,
+            commands::update_sidecar,
+            commands::update_ytdlp,
+            commands::get_ytdlp_version,
+            commands::get_ffmpeg_version,
+            commands::check_ffmpeg_update,
+            commands::test_sidecar_executable,
             commands::get_download_path,
             commands::set_download_path,
+            commands::save_download_path_preset,
+            commands::list_download_path_presets,
+            commands::activate_download_path_preset,
+            commands::probe,
+            commands::check_download_args,
+            commands::validate_batch,
+            commands::get_emit_resume_events,
+            commands::set_emit_resume_events,
+            commands::get_store_save_mode,
+            commands::set_store_save_mode,
+            commands::get_low_footprint,
+            commands::get_custom_headers,
+            commands::set_custom_headers,
+            commands::get_sponsorblock_config,
+            commands::set_sponsorblock_config,
+            commands::get_site_profiles,
+            commands::set_site_profiles,
+            commands::set_low_footprint,
+            commands::get_logs,
+            commands::get_recent_errors,
+            commands::get_log_buffer_size,
+            commands::set_log_buffer_size,
+            commands::get_progress_event_interval,
+            commands::set_progress_event_interval,
+            commands::test_youtube_connectivity,
+            commands::test_proxy,
+            commands::get_effective_config,
+            commands::check_proxy_quic,
+            commands::network_panic_stop,
+            commands::network_resume,
+            commands::get_network_stopped,
+            commands::get_channel_avatar,
+            commands::list_chapters,
+            commands::get_extractor_info,
+            commands::convert_local_file,
+            commands::trim_local_file,
+            commands::benchmark_extraction,
+            commands::check_audio_level,
+            commands::get_filename_template,
+            commands::set_filename_template,
+            commands::get_download_archive_enabled,
+            commands::set_download_archive_enabled,
+            commands::list_archive_entries,
+            commands::remove_archive_entry,
+            commands::clear_download_archive,
+            commands::enqueue_download,
+            commands::get_download_queue,
+            commands::pause_queue,
+            commands::resume_queue,
+            commands::get_power_pause_settings,
+            commands::set_power_pause_settings,
+            commands::get_power_status,
+            commands::remove_queued_download,
+            commands::retry_queued_download,
+            commands::export_queue,
+            commands::import_queue,
+            commands::get_max_concurrent_downloads,
+            commands::set_max_concurrent_downloads,
+            commands::estimate_batch_duration,
+            commands::enqueue_batch,
+            commands::get_batch_summary,
+            commands::retry_failed_items,
+            commands::get_session_download_sizes,
+            commands::get_download_history,
+            commands::clear_download_history,
         ])
         // Setup hook for window customization (desktop only)
         .setup(|app| {
+            // Register shared app state (HTTP client with connection pooling);
+            // the low-footprint setting decides how much it keeps around
+            let low_footprint =
+                settings::get_bool(app.handle(), settings::LOW_FOOTPRINT, false);
+            app.manage(AppState::new(low_footprint));
+
+            // Keep recent log messages for the live log view
+            let log_buffer_size = settings::get_u64(
+                app.handle(),
+                settings::LOG_BUFFER_SIZE,
+                app.state::<AppState>().logs.capacity() as u64,
+            );
+            app.state::<AppState>().logs.resize(log_buffer_size as usize);
+            logs::capture_download_logs(app.handle());
+
             #[cfg(desktop)]
             {
                 // Get main window (desktop only)
@@ -57,6 +176,18 @@ pub fn run() {
                 }
             }
             
+            // Re-arm the safety gate unless the bypass is meant to persist
+            let _ = tauri::async_runtime::block_on(safety::reset_bypass_on_launch(app.handle()));
+
+            // Restore jobs left in the queue by the previous session (paused)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                queue::restore_queue(&handle).await;
+            });
+
+            // Pause the queue on battery / metered connections when enabled
+            power::start_monitor(app.handle().clone());
+
             // Mobile setup - nothing special needed, UI handles it
             #[cfg(mobile)]
             {
@@ -65,6 +196,12 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Write out any debounced store changes before quitting
+            if let tauri::RunEvent::Exit = event {
+                settings::flush_all_stores(app);
+            }
+        });
 }
@@ -0,0 +1,101 @@
+//! In-memory log buffer
+//! Keeps the most recent `download-log` messages for the live log view
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Entries kept by default
+pub const DEFAULT_CAPACITY: usize = 500;
+
+/// Entries kept by default in low-footprint mode
+pub const LOW_FOOTPRINT_CAPACITY: usize = 50;
+
+/// Upper bound so the buffer can't grow without limit
+pub const MAX_CAPACITY: usize = 10_000;
+
+/// One logged message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+}
+
+struct Inner {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+/// Ring buffer of recent log entries, oldest first
+pub struct LogBuffer {
+    inner: Mutex<Inner>,
+}
+
+impl LogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: VecDeque::new(),
+                capacity: capacity.min(MAX_CAPACITY),
+            }),
+        }
+    }
+
+    /// Append an entry, dropping the oldest when full
+    pub fn push(&self, level: String, message: String) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.capacity == 0 {
+            return;
+        }
+        while inner.entries.len() >= inner.capacity {
+            inner.entries.pop_front();
+        }
+        inner.entries.push_back(LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level,
+            message,
+        });
+    }
+
+    /// Snapshot of the buffered entries
+    pub fn entries(&self) -> Vec<LogEntry> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.entries.iter().cloned().collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).capacity
+    }
+
+    /// Change the capacity (clamped to `MAX_CAPACITY`), trimming the oldest entries
+    pub fn resize(&self, capacity: usize) -> usize {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.capacity = capacity.min(MAX_CAPACITY);
+        while inner.entries.len() > inner.capacity {
+            inner.entries.pop_front();
+        }
+        inner.capacity
+    }
+}
+
+/// Buffer `download-log` events as they are emitted
+pub fn capture_download_logs<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    use tauri::{Listener, Manager};
+
+    #[derive(Deserialize)]
+    struct Payload {
+        level: String,
+        message: String,
+    }
+
+    let handle = app.clone();
+    app.listen_any("download-log", move |event| {
+        if let Ok(payload) = serde_json::from_str::<Payload>(event.payload()) {
+            handle
+                .state::<crate::state::AppState>()
+                .logs
+                .push(payload.level, payload.message);
+        }
+    });
+}
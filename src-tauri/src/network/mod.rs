@@ -0,0 +1,57 @@
+//! Network kill switch
+//! A global stop that aborts in-flight network work and refuses new work
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::watch;
+
+/// Error message for work refused or aborted by the kill switch
+pub const STOPPED_MESSAGE: &str = "Network activity is stopped";
+
+/// Shared stop flag; long-running tasks subscribe to be told when it trips
+pub struct NetworkGuard {
+    stopped: AtomicBool,
+    signal: watch::Sender<bool>,
+}
+
+impl Default for NetworkGuard {
+    fn default() -> Self {
+        let (signal, _) = watch::channel(false);
+        Self {
+            stopped: AtomicBool::new(false),
+            signal,
+        }
+    }
+}
+
+impl NetworkGuard {
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Fail fast when network activity is stopped
+    pub fn ensure_allowed(&self) -> Result<(), String> {
+        if self.is_stopped() {
+            Err(STOPPED_MESSAGE.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Trip the kill switch and notify every subscriber
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.signal.send_replace(true);
+    }
+
+    /// Allow network activity again
+    pub fn resume(&self) {
+        self.stopped.store(false, Ordering::SeqCst);
+        self.signal.send_replace(false);
+    }
+
+    /// Resolve once the kill switch trips (immediately if it already has)
+    pub async fn stopped(&self) {
+        let mut rx = self.signal.subscribe();
+        let _ = rx.wait_for(|stopped| *stopped).await;
+    }
+}
@@ -0,0 +1,103 @@
+//! Playlist files
+//! Writes M3U playlists referencing downloaded files and remembers how far
+//! interrupted playlist downloads got
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::commands::download::{DownloadFormat, DownloadOptions, DownloadResult};
+
+/// Settings key holding resume points by playlist URL
+const RESUME_KEY: &str = "playlistResume";
+
+/// How far an unfinished playlist download got, and how it was started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistResume {
+    /// 1-based index of the last entry that was fully processed
+    pub last_index: u32,
+    pub total: u32,
+    pub format: DownloadFormat,
+    pub options: DownloadOptions,
+}
+
+/// Write an extended M3U (UTF-8) listing `entries` in order into `dir`
+///
+/// Paths are relative to `dir` when the file lives inside it, so the folder
+/// can be moved as a whole. Returns the playlist's path.
+pub fn write_m3u(dir: &Path, entries: &[DownloadResult]) -> Result<PathBuf, String> {
+    let mut content = String::from("#EXTM3U\n");
+
+    for entry in entries {
+        let file = Path::new(&entry.output_path);
+        let location = file.strip_prefix(dir).unwrap_or(file);
+        let duration = entry.duration.map(|d| d as i64).unwrap_or(-1);
+        let label = match &entry.artist {
+            Some(artist) => format!("{} - {}", artist, entry.title),
+            None => entry.title.clone(),
+        };
+
+        content.push_str(&format!("#EXTINF:{},{}\n", duration, label));
+        content.push_str(&location.to_string_lossy());
+        content.push('\n');
+    }
+
+    // Batches finishing in the same second get numbered files
+    let stem = format!("playlist-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let mut path = dir.join(format!("{}.m3u8", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.m3u8", stem, n));
+        n += 1;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write playlist: {}", e))?;
+
+    Ok(path)
+}
+
+fn load_resume_points<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> HashMap<String, PlaylistResume> {
+    use tauri_plugin_store::StoreExt;
+
+    app.store(crate::settings::STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(RESUME_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Resume point saved for a playlist URL, if its download didn't finish
+pub fn load_resume<R: tauri::Runtime>(app: &tauri::AppHandle<R>, url: &str) -> Option<PlaylistResume> {
+    load_resume_points(app).remove(url)
+}
+
+/// Remember that entries up to `resume.last_index` of `url` are done
+pub async fn save_resume<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &str,
+    resume: PlaylistResume,
+) -> Result<(), String> {
+    crate::settings::update_store(
+        app,
+        crate::settings::STORE_PATH,
+        RESUME_KEY,
+        |points: &mut HashMap<String, PlaylistResume>| {
+            points.insert(url.to_string(), resume);
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Forget the resume point of a playlist that finished
+pub async fn clear_resume<R: tauri::Runtime>(app: &tauri::AppHandle<R>, url: &str) -> Result<(), String> {
+    crate::settings::update_store(
+        app,
+        crate::settings::STORE_PATH,
+        RESUME_KEY,
+        |points: &mut HashMap<String, PlaylistResume>| {
+            points.remove(url);
+            Ok(())
+        },
+    )
+    .await
+}
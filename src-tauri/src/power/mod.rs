@@ -0,0 +1,227 @@
+//! Power and network cost awareness
+//! Pauses the download queue on battery or a metered connection and resumes
+//! it once the machine is back on AC / an unmetered network
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
+
+use crate::state::AppState;
+
+/// Key for pausing the queue while running on battery
+pub const PAUSE_ON_BATTERY: &str = "pauseOnBattery";
+
+/// Key for pausing the queue while on a metered connection
+pub const PAUSE_ON_METERED: &str = "pauseOnMetered";
+
+/// How often the power and network state is checked
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// Detected power and network state; `None` when the platform can't tell
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PowerStatus {
+    pub on_battery: Option<bool>,
+    pub metered: Option<bool>,
+}
+
+/// Payload for `power-pause` events
+#[derive(Clone, Serialize)]
+struct PowerPausePayload {
+    /// True when the queue was just paused, false when it was resumed
+    paused: bool,
+    /// `battery` or `metered` when pausing
+    reason: Option<&'static str>,
+}
+
+/// Whether the queue is currently paused because of the power/network state
+#[derive(Default)]
+pub struct PowerPause {
+    /// The queue was paused by the monitor rather than the user
+    auto_paused: AtomicBool,
+    /// A pause condition held at the last check
+    triggered: AtomicBool,
+}
+
+impl PowerPause {
+    /// The user paused or resumed by hand, so don't undo it automatically
+    pub fn forget(&self) {
+        self.auto_paused.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Check the current power and network state
+pub async fn detect() -> PowerStatus {
+    PowerStatus {
+        on_battery: on_battery().await,
+        metered: is_metered().await,
+    }
+}
+
+/// Start polling the power and network state
+///
+/// The first check waits one interval, so a queue restored paused at launch
+/// is never resumed by the monitor.
+pub fn start_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(POLL_INTERVAL_SECS)).await;
+            check(&app).await;
+        }
+    });
+}
+
+/// Pause when a pause condition starts to hold, resume when it clears
+async fn check(app: &tauri::AppHandle) {
+    let pause_on_battery = crate::settings::get_bool(app, PAUSE_ON_BATTERY, false);
+    let pause_on_metered = crate::settings::get_bool(app, PAUSE_ON_METERED, false);
+    let state = app.state::<AppState>();
+
+    let reason = if !pause_on_battery && !pause_on_metered {
+        None
+    } else {
+        let status = detect().await;
+        if pause_on_battery && status.on_battery == Some(true) {
+            Some("battery")
+        } else if pause_on_metered && status.metered == Some(true) {
+            Some("metered")
+        } else {
+            None
+        }
+    };
+
+    // Only act on changes so a manual resume on battery sticks
+    let was_triggered = state.power_pause.triggered.swap(reason.is_some(), Ordering::SeqCst);
+    match reason {
+        Some(reason) if !was_triggered => {
+            if state.queue.is_paused() {
+                return;
+            }
+            state.queue.set_paused(true);
+            state.power_pause.auto_paused.store(true, Ordering::SeqCst);
+            let _ = app.emit("power-pause", PowerPausePayload { paused: true, reason: Some(reason) });
+            let _ = app.emit(
+                "download-log",
+                crate::commands::download::LogPayload {
+                    level: "info".to_string(),
+                    message: match reason {
+                        "battery" => "Queue paused: running on battery".to_string(),
+                        _ => "Queue paused: metered connection".to_string(),
+                    },
+                },
+            );
+        }
+        None if was_triggered => {
+            if !state.power_pause.auto_paused.swap(false, Ordering::SeqCst) {
+                return;
+            }
+            state.queue.set_paused(false);
+            crate::queue::start_worker(app.clone());
+            let _ = app.emit("power-pause", PowerPausePayload { paused: false, reason: None });
+            let _ = app.emit(
+                "download-log",
+                crate::commands::download::LogPayload {
+                    level: "info".to_string(),
+                    message: "Queue resumed: back on AC power / unmetered connection".to_string(),
+                },
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Run a system tool and return its stdout, if it succeeded
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = tokio::process::Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW: don't flash a console every poll
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().await.ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "linux")]
+async fn on_battery() -> Option<bool> {
+    let mut has_battery = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Mains" | "USB" => {
+                if std::fs::read_to_string(path.join("online")).is_ok_and(|s| s.trim() == "1") {
+                    return Some(false);
+                }
+            }
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    // Desktops without a battery are always on AC
+    Some(has_battery)
+}
+
+#[cfg(target_os = "macos")]
+async fn on_battery() -> Option<bool> {
+    let output = command_output("pmset", &["-g", "batt"]).await?;
+    Some(output.contains("'Battery Power'"))
+}
+
+#[cfg(target_os = "windows")]
+async fn on_battery() -> Option<bool> {
+    // BatteryStatus 1 means discharging; no battery prints nothing
+    let output = command_output(
+        "powershell",
+        &["-NoProfile", "-Command", "(Get-CimInstance Win32_Battery).BatteryStatus"],
+    )
+    .await?;
+    Some(output.lines().any(|line| line.trim() == "1"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+async fn on_battery() -> Option<bool> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+async fn is_metered() -> Option<bool> {
+    // NetworkManager reports "yes", "no", "yes (guessed)" or "unknown" per device
+    let output = command_output("nmcli", &["-t", "-f", "GENERAL.METERED", "device", "show"]).await?;
+    let values: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("GENERAL.METERED:"))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().any(|v| v.starts_with("yes")))
+}
+
+#[cfg(target_os = "windows")]
+async fn is_metered() -> Option<bool> {
+    let output = command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile().GetConnectionCost().NetworkCostType",
+        ],
+    )
+    .await?;
+    match output.trim() {
+        "Unrestricted" => Some(false),
+        "Fixed" | "Variable" => Some(true),
+        _ => None,
+    }
+}
+
+/// macOS has no command-line view of Low Data Mode
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+async fn is_metered() -> Option<bool> {
+    None
+}
@@ -0,0 +1,94 @@
+//! Per-site option profiles
+//! Default download options applied by matching the URL's host
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::download::{DownloadError, DownloadOptions, OptionOverrides};
+
+/// Settings key holding the profile list
+const PROFILES_KEY: &str = "siteProfiles";
+
+/// Default options for URLs whose host matches `host_pattern`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteProfile {
+    /// Host such as `soundcloud.com` (subdomains included) or `*.bandcamp.com`
+    pub host_pattern: String,
+    #[serde(default)]
+    pub options: DownloadOptions,
+}
+
+impl SiteProfile {
+    /// Check whether a host is covered by this profile
+    fn matches(&self, host: &str) -> bool {
+        let pattern = normalize_pattern(&self.host_pattern);
+        !pattern.is_empty() && (host == pattern || host.ends_with(&format!(".{}", pattern)))
+    }
+}
+
+/// Lowercase a host pattern and strip wildcard/leading-dot prefixes
+fn normalize_pattern(pattern: &str) -> String {
+    pattern
+        .trim()
+        .trim_start_matches("*.")
+        .trim_start_matches('.')
+        .to_lowercase()
+}
+
+/// Load the profile list from the settings store
+pub fn load_profiles<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<SiteProfile> {
+    use tauri_plugin_store::StoreExt;
+
+    app.store(crate::settings::STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(PROFILES_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Save the profile list, normalizing patterns and dropping empty ones
+pub fn save_profiles<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profiles: &[SiteProfile],
+) -> Result<(), String> {
+    let profiles: Vec<SiteProfile> = profiles
+        .iter()
+        .map(|p| SiteProfile {
+            host_pattern: normalize_pattern(&p.host_pattern),
+            options: p.options.clone(),
+        })
+        .filter(|p| !p.host_pattern.is_empty())
+        .collect();
+
+    crate::settings::set_value(
+        app,
+        PROFILES_KEY,
+        serde_json::to_value(profiles).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
+/// The most specific profile matching the URL's host
+pub fn matching_profile<R: tauri::Runtime>(app: &tauri::AppHandle<R>, url: &str) -> Option<SiteProfile> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_lowercase();
+
+    // The longest matching pattern is the most specific one
+    load_profiles(app)
+        .into_iter()
+        .filter(|p| p.matches(&host))
+        .max_by_key(|p| normalize_pattern(&p.host_pattern).len())
+}
+
+/// Resolve the options a download of `url` runs with: the per-call
+/// overrides layered over the most specific matching profile
+///
+/// Every entry point that starts, queues or checks a download goes through
+/// here so a profile applies the same way everywhere.
+pub fn resolve_options<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &str,
+    options: &OptionOverrides,
+) -> Result<DownloadOptions, DownloadError> {
+    match matching_profile(app, url) {
+        Some(profile) => options.merged_over(&profile.options),
+        None => options.to_options(),
+    }
+}
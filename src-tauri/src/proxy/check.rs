@@ -0,0 +1,215 @@
+//! Network reachability checks
+//! Times lightweight requests directly or through a configured proxy
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::{ProxyConfig, ProxyType};
+
+/// Endpoint YouTube answers with an empty 204, ideal for reachability checks
+pub const YOUTUBE_204_URL: &str = "https://www.youtube.com/generate_204";
+
+/// Per-request timeout so a dead route fails fast instead of hanging
+const CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// Proxies slower than this are pruned from the pool by default
+pub const DEFAULT_MAX_LATENCY_MS: u64 = 5000;
+
+/// Outcome of a single reachability check
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityResult {
+    pub ok: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Time a GET request against `url` using `client`
+pub async fn check_endpoint(client: &reqwest::Client, url: &str) -> ConnectivityResult {
+    let started = Instant::now();
+    let response = client
+        .get(url)
+        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .send()
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match response {
+        Ok(resp) if resp.status().is_success() => ConnectivityResult {
+            ok: true,
+            latency_ms: Some(latency_ms),
+            error: None,
+        },
+        Ok(resp) => ConnectivityResult {
+            ok: false,
+            latency_ms: Some(latency_ms),
+            error: Some(format!("Unexpected HTTP status {}", resp.status())),
+        },
+        Err(e) => ConnectivityResult {
+            ok: false,
+            latency_ms: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Build a short-lived client that routes all traffic through `config`
+pub fn build_proxied_client(config: &ProxyConfig) -> Result<reqwest::Client, String> {
+    let url = config
+        .to_url()
+        .ok_or_else(|| "Proxy is not enabled".to_string())?;
+    let proxy = reqwest::Proxy::all(url.as_str()).map_err(|e| format!("Invalid proxy: {}", e))?;
+
+    reqwest::Client::builder()
+        .proxy(proxy)
+        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create proxy client: {}", e))
+}
+
+/// Outcome of pruning the proxy pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneResult {
+    pub kept: usize,
+    pub removed: usize,
+}
+
+/// Test every proxy concurrently and keep those that respond within `max_latency_ms`
+pub async fn filter_healthy(pool: Vec<ProxyConfig>, max_latency_ms: u64) -> Vec<ProxyConfig> {
+    let checks = pool.iter().map(|config| async move {
+        match build_proxied_client(config) {
+            Ok(client) => check_endpoint(&client, YOUTUBE_204_URL).await,
+            Err(e) => ConnectivityResult {
+                ok: false,
+                latency_ms: None,
+                error: Some(e),
+            },
+        }
+    });
+    let results = join_all(checks).await;
+
+    pool.into_iter()
+        .zip(results)
+        .filter(|(_, result)| {
+            result.ok && result.latency_ms.is_some_and(|ms| ms <= max_latency_ms)
+        })
+        .map(|(config, _)| config)
+        .collect()
+}
+
+/// Whether a proxy can carry the QUIC (HTTP/3) traffic YouTube prefers for media
+#[derive(Debug, Clone, Serialize)]
+pub struct QuicSupport {
+    /// The proxy accepted a UDP relay (SOCKS5 UDP ASSOCIATE)
+    pub udp_relay: bool,
+    /// YouTube advertised HTTP/3 on the response that came through the proxy
+    pub h3_advertised: bool,
+    /// HTTP version the proxied request ended up on, e.g. `HTTP/1.1`
+    pub http_version: Option<String>,
+    pub detail: String,
+}
+
+/// Check whether `config` can forward QUIC, falling back to a plain TCP probe
+///
+/// QUIC runs over UDP: an HTTP proxy only tunnels TCP, and a SOCKS5 proxy
+/// only carries it if it grants UDP ASSOCIATE. Without it, yt-dlp still works
+/// but media comes over HTTP/1.1 or HTTP/2, which can be noticeably slower.
+pub async fn check_quic_support(config: &ProxyConfig) -> QuicSupport {
+    let (http_version, h3_advertised, tcp_error) = match build_proxied_client(config) {
+        Ok(client) => match client.get(YOUTUBE_204_URL).send().await {
+            Ok(resp) => {
+                let h3 = resp
+                    .headers()
+                    .get(reqwest::header::ALT_SVC)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|v| v.contains("h3"));
+                (Some(format!("{:?}", resp.version())), h3, None)
+            }
+            Err(e) => (None, false, Some(e.to_string())),
+        },
+        Err(e) => (None, false, Some(e)),
+    };
+
+    let udp = match config.proxy_type {
+        ProxyType::Socks5 => {
+            match tokio::time::timeout(
+                Duration::from_secs(CHECK_TIMEOUT_SECS),
+                socks5_udp_associate(config),
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(_) => Err("Timed out negotiating with the proxy".to_string()),
+            }
+        }
+        _ => Ok(false),
+    };
+
+    let detail = match (&udp, tcp_error) {
+        (Err(e), _) => format!("Could not test UDP relay: {}", e),
+        (Ok(true), _) => "Proxy relays UDP, so QUIC/HTTP3 can pass through".to_string(),
+        (Ok(false), Some(e)) => format!("Proxy request failed: {}", e),
+        (Ok(false), None) if matches!(config.proxy_type, ProxyType::Http | ProxyType::Https) => {
+            "HTTP proxies only tunnel TCP; media falls back from QUIC to HTTP/1.1 or HTTP/2"
+                .to_string()
+        }
+        (Ok(false), None) if config.proxy_type == ProxyType::Socks4 => {
+            "SOCKS4 has no UDP relay; media falls back from QUIC to HTTP/1.1 or HTTP/2".to_string()
+        }
+        (Ok(false), None) => {
+            "Proxy refused UDP relay; media falls back from QUIC to HTTP/1.1 or HTTP/2".to_string()
+        }
+    };
+
+    QuicSupport {
+        udp_relay: udp.unwrap_or(false),
+        h3_advertised,
+        http_version,
+        detail,
+    }
+}
+
+/// Ask a SOCKS5 proxy for a UDP relay; Ok(false) if it refuses
+async fn socks5_udp_associate(config: &ProxyConfig) -> Result<bool, String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let io_error = |e: std::io::Error| e.to_string();
+    let mut stream = tokio::net::TcpStream::connect((config.host.as_str(), config.port))
+        .await
+        .map_err(io_error)?;
+
+    // Offer username/password auth only when credentials are configured
+    let auth = config.auth.as_ref().filter(|auth| !auth.is_empty());
+    let greeting: &[u8] = if auth.is_some() { &[5, 2, 0, 2] } else { &[5, 1, 0] };
+    stream.write_all(greeting).await.map_err(io_error)?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await.map_err(io_error)?;
+
+    match (reply[1], auth) {
+        (0, _) => {}
+        (2, Some(auth)) => {
+            let (user, pass) = (auth.username.as_bytes(), auth.password.as_bytes());
+            if user.len() > 255 || pass.len() > 255 {
+                return Err("Proxy credentials are too long for SOCKS5".to_string());
+            }
+            let mut request = vec![1, user.len() as u8];
+            request.extend_from_slice(user);
+            request.push(pass.len() as u8);
+            request.extend_from_slice(pass);
+            stream.write_all(&request).await.map_err(io_error)?;
+            stream.read_exact(&mut reply).await.map_err(io_error)?;
+            if reply[1] != 0 {
+                return Err("Proxy rejected the credentials".to_string());
+            }
+        }
+        _ => return Err("Proxy offered no usable authentication method".to_string()),
+    }
+
+    // UDP ASSOCIATE from an unspecified address; reply code 0 means granted
+    stream
+        .write_all(&[5, 3, 0, 1, 0, 0, 0, 0, 0, 0])
+        .await
+        .map_err(io_error)?;
+    stream.read_exact(&mut reply).await.map_err(io_error)?;
+    Ok(reply[1] == 0)
+}
@@ -1,5 +1,9 @@
 //! Proxy module
 
+pub mod check;
 pub mod config;
+pub mod rotation;
 
+pub use check::*;
 pub use config::*;
+pub use rotation::*;
@@ -0,0 +1,127 @@
+//! Proxy pool rotation
+//! Picks a proxy from the saved pool per download, skipping ones that failed a test
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+
+use super::{load_proxy_pool, resolve_proxy_config, ProxyConfig, STORE_PATH};
+use crate::state::AppState;
+
+/// How downloads choose a proxy from the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyRotation {
+    /// Always use the single configured proxy
+    #[default]
+    Off,
+    /// Each download takes the next proxy in the pool
+    Sequential,
+    /// Each download takes a random proxy from the pool
+    Random,
+}
+
+/// Rotation cursor and proxies known to be dead this session
+#[derive(Default)]
+pub struct ProxyRotator {
+    next: AtomicUsize,
+    /// `host:port` of proxies that failed their last test
+    dead: Mutex<HashSet<String>>,
+}
+
+impl ProxyRotator {
+    /// Record a test result so dead proxies are skipped until they pass again
+    pub fn mark(&self, config: &ProxyConfig, ok: bool) {
+        let mut dead = self.dead.lock().unwrap_or_else(|e| e.into_inner());
+        if ok {
+            dead.remove(&config.address());
+        } else {
+            dead.insert(config.address());
+        }
+    }
+
+    /// Pick a live proxy from `pool`, or `None` when every one is dead
+    pub fn pick(&self, pool: &[ProxyConfig], rotation: ProxyRotation) -> Option<ProxyConfig> {
+        let dead = self.dead.lock().unwrap_or_else(|e| e.into_inner());
+        let live: Vec<&ProxyConfig> = pool
+            .iter()
+            .filter(|p| p.is_enabled() && !dead.contains(&p.address()))
+            .collect();
+        if live.is_empty() {
+            return None;
+        }
+
+        let index = match rotation {
+            ProxyRotation::Random => rand::rng().random_range(0..live.len()),
+            _ => self.next.fetch_add(1, Ordering::Relaxed) % live.len(),
+        };
+        Some(live[index].clone())
+    }
+
+    /// The live proxy `pick` would return next in sequence, without moving the cursor
+    pub fn peek(&self, pool: &[ProxyConfig]) -> Option<ProxyConfig> {
+        let dead = self.dead.lock().unwrap_or_else(|e| e.into_inner());
+        let live: Vec<&ProxyConfig> = pool
+            .iter()
+            .filter(|p| p.is_enabled() && !dead.contains(&p.address()))
+            .collect();
+        if live.is_empty() {
+            return None;
+        }
+
+        Some(live[self.next.load(Ordering::Relaxed) % live.len()].clone())
+    }
+}
+
+/// Load the rotation mode from store
+pub fn load_rotation<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> ProxyRotation {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get("rotation"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Save the rotation mode to store
+pub fn save_rotation<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    rotation: ProxyRotation,
+) -> Result<(), String> {
+    crate::settings::write_store(
+        app,
+        STORE_PATH,
+        "rotation",
+        serde_json::to_value(rotation).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
+/// Proxy for the next download: one from the pool when rotating, otherwise
+/// (or when the whole pool is dead) the regular config
+pub fn select_proxy<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> ProxyConfig {
+    let rotation = load_rotation(app);
+    if rotation != ProxyRotation::Off {
+        let pool = load_proxy_pool(app);
+        if let Some(proxy) = app.state::<AppState>().proxy_rotator.pick(&pool, rotation) {
+            return proxy;
+        }
+    }
+    resolve_proxy_config(app)
+}
+
+/// Proxy the next download would likely use, for dry runs
+///
+/// Leaves the rotation cursor where it is; with random rotation any live
+/// proxy could be picked, so this just returns one of them.
+pub fn peek_proxy<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> ProxyConfig {
+    if load_rotation(app) != ProxyRotation::Off {
+        let pool = load_proxy_pool(app);
+        if let Some(proxy) = app.state::<AppState>().proxy_rotator.peek(&pool) {
+            return proxy;
+        }
+    }
+    resolve_proxy_config(app)
+}
@@ -0,0 +1,182 @@
+//! Batch tracking
+//! Per-item results of multi-URL enqueues, kept so failed items can be retried
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+
+use super::{save_queue, start_worker, JobStatus, QueuedJob, STORE_PATH};
+use crate::commands::download::{DownloadFormat, OptionOverrides};
+use crate::state::AppState;
+
+/// Outcome of one batch item so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// One URL of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItem {
+    pub job_id: String,
+    pub url: String,
+    pub status: ItemStatus,
+    pub error: Option<String>,
+}
+
+/// A group of downloads enqueued together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    pub format: DownloadFormat,
+    /// Options as sent for the batch, layered over each URL's site profile
+    #[serde(default)]
+    pub options: OptionOverrides,
+    pub items: Vec<BatchItem>,
+}
+
+/// Counts and items of a batch for the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub id: String,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub pending: usize,
+    pub items: Vec<BatchItem>,
+}
+
+impl Batch {
+    pub fn summary(&self) -> BatchSummary {
+        let count = |status| self.items.iter().filter(|i| i.status == status).count();
+        BatchSummary {
+            id: self.id.clone(),
+            total: self.items.len(),
+            completed: count(ItemStatus::Completed),
+            failed: count(ItemStatus::Failed),
+            pending: count(ItemStatus::Pending),
+            items: self.items.clone(),
+        }
+    }
+}
+
+/// Persist batch records next to the queue
+pub fn save_batches<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    batches: &HashMap<String, Batch>,
+) -> Result<(), String> {
+    crate::settings::write_store(
+        app,
+        STORE_PATH,
+        "batches",
+        serde_json::to_value(batches).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
+/// Load persisted batch records
+pub fn load_batches<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> HashMap<String, Batch> {
+    let store = match app.store(STORE_PATH) {
+        Ok(s) => s,
+        Err(_) => return HashMap::new(),
+    };
+
+    store
+        .get("batches")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Record how a batch job ended
+pub async fn record_result(
+    app: &tauri::AppHandle,
+    batch_id: &str,
+    job_id: &str,
+    result: Result<(), String>,
+) {
+    let state = app.state::<AppState>();
+    let mut batches = state.queue.batches.lock().await;
+
+    let item = batches
+        .get_mut(batch_id)
+        .and_then(|batch| batch.items.iter_mut().find(|item| item.job_id == job_id));
+    if let Some(item) = item {
+        match result {
+            Ok(()) => {
+                item.status = ItemStatus::Completed;
+                item.error = None;
+            }
+            Err(e) => {
+                item.status = ItemStatus::Failed;
+                item.error = Some(e);
+            }
+        }
+        let _ = save_batches(app, &batches);
+    }
+}
+
+/// Where a job sits in its batch, if the batch still knows it
+pub async fn item_position(app: &tauri::AppHandle, batch_id: &str, job_id: &str) -> Option<usize> {
+    let state = app.state::<AppState>();
+    let batches = state.queue.batches.lock().await;
+    batches
+        .get(batch_id)?
+        .items
+        .iter()
+        .position(|item| item.job_id == job_id)
+}
+
+/// Put a batch's failed items back into the queue and return how many
+pub async fn retry_failed(app: &tauri::AppHandle, batch_id: &str) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    let mut batches = state.queue.batches.lock().await;
+    let batch = batches
+        .get_mut(batch_id)
+        .ok_or_else(|| format!("Unknown batch: {}", batch_id))?;
+
+    let mut jobs = state.queue.jobs.lock().await;
+    let mut retried = 0;
+    for item in batch
+        .items
+        .iter_mut()
+        .filter(|item| item.status == ItemStatus::Failed)
+    {
+        // Reuse the failed job if it is still listed, otherwise queue it again
+        match jobs.iter_mut().find(|job| job.id == item.job_id) {
+            Some(job) => {
+                job.status = JobStatus::Pending;
+                job.error = None;
+            }
+            None => {
+                let options = crate::profiles::resolve_options(app, &item.url, &batch.options)
+                    .map_err(|e| e.to_string())?;
+                jobs.push_back(QueuedJob {
+                    id: item.job_id.clone(),
+                    url: item.url.clone(),
+                    format: batch.format,
+                    options,
+                    batch_id: Some(batch_id.to_string()),
+                    status: JobStatus::Pending,
+                    error: None,
+                });
+            }
+        }
+
+        item.status = ItemStatus::Pending;
+        item.error = None;
+        retried += 1;
+    }
+
+    save_queue(app, &jobs)?;
+    save_batches(app, &batches)?;
+    drop(jobs);
+    drop(batches);
+
+    if retried > 0 {
+        start_worker(app.clone());
+    }
+    Ok(retried)
+}
@@ -0,0 +1,363 @@
+//! Download queue
+//! Jobs waiting to be downloaded, persisted so a batch survives restarts
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tauri::{Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::download::{
+    execute_download, get_download_dir, validate_url, DownloadFormat, DownloadOptions, DownloadResult,
+    LogPayload,
+};
+use crate::state::AppState;
+
+pub mod batch;
+
+pub(crate) const STORE_PATH: &str = "download_queue.json";
+
+/// Downloads the queue runs at once unless configured otherwise
+pub const DEFAULT_MAX_CONCURRENT: u64 = 1;
+
+/// Upper bound for concurrent queue downloads
+pub const MAX_CONCURRENT: u64 = 5;
+
+/// Lifecycle of a queued job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Failed,
+}
+
+/// A download waiting in (or being processed from) the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub id: String,
+    pub url: String,
+    pub format: DownloadFormat,
+    #[serde(default)]
+    pub options: DownloadOptions,
+    /// Batch this job was enqueued with, if any
+    #[serde(default)]
+    pub batch_id: Option<String>,
+    pub status: JobStatus,
+    pub error: Option<String>,
+}
+
+/// Queue state held in `AppState`
+#[derive(Default)]
+pub struct DownloadQueue {
+    pub jobs: tokio::sync::Mutex<VecDeque<QueuedJob>>,
+    /// Paused queues keep their jobs but start nothing new
+    paused: AtomicBool,
+    /// Jobs currently downloading; only incremented while `jobs` is locked
+    running: AtomicUsize,
+    /// Finished downloads that asked for a playlist file, by batch id
+    /// (`None` for jobs enqueued on their own)
+    playlist_entries: tokio::sync::Mutex<HashMap<Option<String>, Vec<PlaylistEntry>>>,
+    /// Jobs claimed since launch; orders single jobs in their playlist
+    claims: AtomicUsize,
+    /// Per-item results of enqueued batches, by batch id
+    pub batches: tokio::sync::Mutex<HashMap<String, batch::Batch>>,
+}
+
+/// A downloaded file waiting for its batch's playlist
+struct PlaylistEntry {
+    /// Position of the job in its batch (claim order for single jobs)
+    order: usize,
+    result: DownloadResult,
+}
+
+/// One job's place in the queue, as sent in `queue-updated` events
+#[derive(Clone, Serialize)]
+struct QueueItemPayload {
+    id: String,
+    url: String,
+    /// 1-based position in the queue
+    position: usize,
+    status: JobStatus,
+    error: Option<String>,
+}
+
+/// Payload for `playlist-written` events
+#[derive(Clone, Serialize)]
+struct PlaylistWrittenPayload {
+    path: String,
+    entries: usize,
+}
+
+impl DownloadQueue {
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+}
+
+/// Persist the queue so it can be restored after a restart
+///
+/// Every change goes through here, so it also emits `queue-updated`.
+pub fn save_queue<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    jobs: &VecDeque<QueuedJob>,
+) -> Result<(), String> {
+    let items: Vec<QueueItemPayload> = jobs
+        .iter()
+        .enumerate()
+        .map(|(i, job)| QueueItemPayload {
+            id: job.id.clone(),
+            url: job.url.clone(),
+            position: i + 1,
+            status: job.status,
+            error: job.error.clone(),
+        })
+        .collect();
+    let _ = app.emit("queue-updated", items);
+
+    crate::settings::write_store(
+        app,
+        STORE_PATH,
+        "jobs",
+        serde_json::to_value(jobs).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
+/// Load the persisted queue from the store
+fn load_queue<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> VecDeque<QueuedJob> {
+    let store = match app.store(STORE_PATH) {
+        Ok(s) => s,
+        Err(_) => return VecDeque::new(),
+    };
+
+    store
+        .get("jobs")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Restore the persisted queue at launch
+///
+/// Jobs interrupted mid-download go back to pending, jobs with URLs that no
+/// longer validate are marked failed, and the queue starts paused so nothing
+/// downloads until the user confirms.
+pub async fn restore_queue(app: &tauri::AppHandle) {
+    let mut restored = load_queue(app);
+    for job in restored.iter_mut() {
+        if job.status == JobStatus::Running {
+            job.status = JobStatus::Pending;
+        }
+        if let Err(e) = validate_url(&job.url) {
+            job.status = JobStatus::Failed;
+            job.error = Some(e.to_string());
+        }
+    }
+
+    let state = app.state::<AppState>();
+    if restored.iter().any(|job| job.status == JobStatus::Pending) {
+        state.queue.set_paused(true);
+    }
+
+    {
+        let mut batches = state.queue.batches.lock().await;
+        let mut restored_batches = batch::load_batches(app);
+        restored_batches.extend(batches.drain());
+        *batches = restored_batches;
+    }
+
+    // Keep anything enqueued before the restore finished
+    let mut jobs = state.queue.jobs.lock().await;
+    restored.extend(jobs.drain(..));
+    *jobs = restored;
+    let _ = save_queue(app, &jobs);
+}
+
+/// Configured number of queue downloads that may run at once
+pub fn max_concurrent<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> usize {
+    crate::settings::get_u64(
+        app,
+        crate::settings::MAX_CONCURRENT_DOWNLOADS,
+        DEFAULT_MAX_CONCURRENT,
+    )
+    .clamp(1, MAX_CONCURRENT) as usize
+}
+
+/// Start pending jobs until the concurrency limit is reached
+///
+/// Called whenever a job is added or finishes; once a batch has nothing
+/// pending or running, its playlist is written.
+pub fn start_worker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        dispatch(&app).await;
+    });
+}
+
+async fn dispatch(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    let limit = max_concurrent(app);
+
+    let (claimed, finished) = {
+        let mut jobs = state.queue.jobs.lock().await;
+        let mut claimed = Vec::new();
+        while !state.queue.is_paused() && state.queue.running.load(Ordering::SeqCst) < limit {
+            let Some(job) = jobs.iter_mut().find(|job| job.status == JobStatus::Pending) else {
+                break;
+            };
+            job.status = JobStatus::Running;
+            let order = state.queue.claims.fetch_add(1, Ordering::SeqCst);
+            claimed.push((job.clone(), order));
+            state.queue.running.fetch_add(1, Ordering::SeqCst);
+        }
+        if !claimed.is_empty() {
+            let _ = save_queue(app, &jobs);
+        }
+
+        // Entries are added while `jobs` is locked, so none can slip in here
+        let mut entries = state.queue.playlist_entries.lock().await;
+        let done: Vec<Option<String>> = entries
+            .keys()
+            .filter(|batch_id| {
+                !jobs
+                    .iter()
+                    .any(|job| &job.batch_id == *batch_id && job.status != JobStatus::Failed)
+            })
+            .cloned()
+            .collect();
+        let finished: Vec<Vec<PlaylistEntry>> =
+            done.iter().filter_map(|batch_id| entries.remove(batch_id)).collect();
+        (claimed, finished)
+    };
+
+    for entries in finished {
+        write_batch_playlist(app, entries);
+    }
+
+    for (job, order) in claimed {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            run_job(&app, job, order).await;
+            app.state::<AppState>()
+                .queue
+                .running
+                .fetch_sub(1, Ordering::SeqCst);
+            // Fill the freed slot; a failed job never blocks the rest
+            start_worker(app);
+        });
+    }
+}
+
+/// Download one claimed job and record its outcome
+///
+/// `order` places a single job's files in its playlist; batch jobs use their
+/// position in the batch instead.
+async fn run_job(app: &tauri::AppHandle, job: QueuedJob, order: usize) {
+    let state = app.state::<AppState>();
+
+    // A queued job is meant to run eventually, so wait out a cooldown
+    // instead of failing straight away
+    if let Some(wait_secs) = state.url_cooldowns.remaining_secs(&job.url) {
+        let _ = app.emit(
+            "download-log",
+            LogPayload {
+                level: "info".to_string(),
+                message: format!("Waiting {}s before retrying {}", wait_secs, job.url),
+            },
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(wait_secs)) => {}
+            // execute_download then fails the job as stopped
+            _ = state.network.stopped() => {}
+        }
+    }
+
+    let result = execute_download(
+        app.clone(),
+        job.id.clone(),
+        job.url.clone(),
+        job.format,
+        job.options.clone(),
+    )
+    .await;
+
+    let order = match &job.batch_id {
+        Some(batch_id) => batch::item_position(app, batch_id, &job.id).await.unwrap_or(order),
+        None => order,
+    };
+
+    let outcome = {
+        let mut jobs = state.queue.jobs.lock().await;
+        let outcome = match result {
+            Ok(download) => {
+                if job.options.write_playlist_file && !download.skipped {
+                    // A playlist job lists each of its entries
+                    let results = if download.playlist_items.is_empty() {
+                        vec![download]
+                    } else {
+                        download.playlist_items
+                    };
+                    state
+                        .queue
+                        .playlist_entries
+                        .lock()
+                        .await
+                        .entry(job.batch_id.clone())
+                        .or_default()
+                        .extend(results.into_iter().map(|result| PlaylistEntry { order, result }));
+                }
+                jobs.retain(|j| j.id != job.id);
+                Ok(())
+            }
+            Err(e) => {
+                if let Some(j) = jobs.iter_mut().find(|j| j.id == job.id) {
+                    j.status = JobStatus::Failed;
+                    j.error = Some(e.to_string());
+                }
+                Err(e.to_string())
+            }
+        };
+        let _ = save_queue(app, &jobs);
+        outcome
+    };
+
+    if let Some(batch_id) = &job.batch_id {
+        batch::record_result(app, batch_id, &job.id, outcome).await;
+    }
+}
+
+/// Write the playlist for a finished batch and emit `playlist-written`
+///
+/// Failed and skipped items never reach the entry list, so they are omitted.
+/// Entries follow the batch order, not the order downloads finished in.
+fn write_batch_playlist(app: &tauri::AppHandle, mut entries: Vec<PlaylistEntry>) {
+    if entries.is_empty() {
+        return;
+    }
+    // Stable, so the items of one playlist job keep their order
+    entries.sort_by_key(|entry| entry.order);
+    let entries: Vec<DownloadResult> = entries.into_iter().map(|entry| entry.result).collect();
+
+    match crate::playlist::write_m3u(&get_download_dir(app), &entries) {
+        Ok(path) => {
+            let _ = app.emit(
+                "playlist-written",
+                PlaylistWrittenPayload {
+                    path: path.to_string_lossy().to_string(),
+                    entries: entries.len(),
+                },
+            );
+        }
+        Err(e) => {
+            let _ = app.emit(
+                "download-log",
+                LogPayload {
+                    level: "warn".to_string(),
+                    message: e,
+                },
+            );
+        }
+    }
+}
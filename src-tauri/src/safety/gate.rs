@@ -1,16 +1,61 @@
 //! Safety Gate logic for download limits and IP protection
 
-use chrono::Local;
+use chrono::{Local, Utc};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::Emitter;
 use tauri_plugin_store::StoreExt;
 
-const STORE_PATH: &str = "safety_gate.json";
-/// Maximum downloads per day before strict locking. 
+pub(crate) const STORE_PATH: &str = "safety_gate.json";
+/// Default maximum downloads per day before strict locking.
 /// 40 is a safe threshold for most residential IPs to avoid YouTube 429 rate-limiting.
-const DAILY_LIMIT: u32 = 40;
-/// Threshold to start warning the user about potential IP rate-limiting.
+const DEFAULT_DAILY_LIMIT: u32 = 40;
+/// Default threshold to start warning the user about potential IP rate-limiting.
 /// 25 allows for a safe "warm-up" period before reaching the strict limit.
-const WARNING_THRESHOLD: u32 = 25;
+const DEFAULT_WARNING_THRESHOLD: u32 = 25;
+
+/// Clock moving backwards by more than this is treated as skew, not jitter
+const BACKWARD_TOLERANCE_SECS: i64 = 5 * 60;
+/// Wall clock outrunning real elapsed time by more than this is treated as skew
+const FORWARD_TOLERANCE_SECS: i64 = 60 * 60;
+
+/// Monotonic reference taken at the previous check in this session, with the
+/// wall-clock time at that moment
+static CLOCK_ANCHOR: Lazy<Mutex<Option<(Instant, i64)>>> = Lazy::new(|| Mutex::new(None));
+
+/// `last_seen` of the backward jump already announced; the jump lasts until
+/// real time catches up, so it is only reported once
+static REPORTED_BACKWARD_JUMP: Lazy<Mutex<Option<i64>>> = Lazy::new(|| Mutex::new(None));
+
+/// Direction of a detected system clock change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockDirection {
+    Backward,
+    Forward,
+}
+
+/// A system clock change large enough to affect the daily reset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockJump {
+    pub direction: ClockDirection,
+    /// Size of the jump in seconds
+    pub secs: i64,
+}
+
+/// Compare the wall clock against this session's monotonic clock and
+/// re-anchor; `Some` when the wall clock ran ahead of real time
+fn detect_forward_jump(now: i64) -> Option<i64> {
+    let mut anchor = CLOCK_ANCHOR.lock().unwrap_or_else(|e| e.into_inner());
+    let jump = anchor.and_then(|(instant, wall)| {
+        let drift = (now - wall) - instant.elapsed().as_secs() as i64;
+        (drift > FORWARD_TOLERANCE_SECS).then_some(drift)
+    });
+    *anchor = Some((Instant::now(), now));
+    jump
+}
 
 /// Safety gate status
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +77,9 @@ pub struct SafetyGateData {
     pub count_date: Option<String>,
     /// Whether user has bypassed the warning
     pub bypass_enabled: bool,
+    /// Latest UTC timestamp seen, to notice the clock being turned back
+    #[serde(default)]
+    pub last_seen: Option<i64>,
 }
 
 impl SafetyGateData {
@@ -48,26 +96,60 @@ impl SafetyGateData {
             .unwrap_or(false)
     }
 
-    /// Reset count if it's a new day
-    pub fn check_daily_reset(&mut self) {
+    /// Fix a count stored without its date (older versions, partial writes)
+    ///
+    /// The count is assumed to be today's so a broken record can never
+    /// under-count. Returns whether anything changed.
+    pub fn repair(&mut self) -> bool {
+        if self.count_date.is_none() && self.daily_count > 0 {
+            self.count_date = Some(Self::today_string());
+            return true;
+        }
+        false
+    }
+
+    /// Reset count if it's a new day, reporting clock changes
+    ///
+    /// A clock turned back keeps the count until real time catches up with
+    /// the latest timestamp seen. A forward jump is only reported: the
+    /// monotonic clock stops during sleep, so waking a suspended machine
+    /// looks the same and must still reset the count on a new day.
+    pub fn check_daily_reset(&mut self) -> Option<ClockJump> {
+        let now = Utc::now().timestamp();
+
+        if let Some(last_seen) = self.last_seen {
+            if last_seen - now > BACKWARD_TOLERANCE_SECS {
+                return Some(ClockJump {
+                    direction: ClockDirection::Backward,
+                    secs: last_seen - now,
+                });
+            }
+        }
+        self.last_seen = Some(self.last_seen.map_or(now, |last| last.max(now)));
+
         if !self.is_today() {
             self.daily_count = 0;
             self.count_date = Some(Self::today_string());
             self.bypass_enabled = false;
         }
+
+        detect_forward_jump(now).map(|secs| ClockJump {
+            direction: ClockDirection::Forward,
+            secs,
+        })
     }
 
-    /// Increment the download counter
-    pub fn increment(&mut self) {
-        self.check_daily_reset();
-        self.daily_count += 1;
+    /// Add `count` downloads to the counter
+    pub fn increment(&mut self, count: u32) {
+        let _ = self.check_daily_reset();
+        self.daily_count = self.daily_count.saturating_add(count);
     }
 
-    /// Get the current gate status
-    pub fn get_status(&self) -> GateStatus {
-        if self.daily_count >= DAILY_LIMIT && !self.bypass_enabled {
+    /// Get the current gate status against the configured thresholds
+    pub fn get_status(&self, config: &SafetyGateConfig) -> GateStatus {
+        if self.daily_count >= config.daily_limit && !self.bypass_enabled {
             GateStatus::Locked
-        } else if self.daily_count >= WARNING_THRESHOLD && !self.bypass_enabled {
+        } else if self.daily_count >= config.warning_threshold && !self.bypass_enabled {
             GateStatus::Warning
         } else {
             GateStatus::Open
@@ -75,11 +157,125 @@ impl SafetyGateData {
     }
 }
 
+/// User-configurable safety gate settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyGateConfig {
+    /// Hosts that don't count against the gate (subdomains included)
+    #[serde(default)]
+    pub exempt_domains: Vec<String>,
+    /// Keep the bypass across app restarts (it always resets on a new day).
+    /// When false, every launch starts with the gate re-armed.
+    #[serde(default = "default_persistent_bypass")]
+    pub persistent_bypass: bool,
+    /// Downloads per day before the gate locks
+    #[serde(default = "default_daily_limit")]
+    pub daily_limit: u32,
+    /// Downloads per day before the gate starts warning
+    #[serde(default = "default_warning_threshold")]
+    pub warning_threshold: u32,
+}
+
+fn default_persistent_bypass() -> bool {
+    true
+}
+
+fn default_daily_limit() -> u32 {
+    DEFAULT_DAILY_LIMIT
+}
+
+fn default_warning_threshold() -> u32 {
+    DEFAULT_WARNING_THRESHOLD
+}
+
+impl Default for SafetyGateConfig {
+    fn default() -> Self {
+        Self {
+            exempt_domains: Vec::new(),
+            persistent_bypass: default_persistent_bypass(),
+            daily_limit: DEFAULT_DAILY_LIMIT,
+            warning_threshold: DEFAULT_WARNING_THRESHOLD,
+        }
+    }
+}
+
+impl SafetyGateConfig {
+    /// Check whether a URL's host is on the exempt list
+    pub fn is_exempt(&self, url: &str) -> bool {
+        let host = match url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_lowercase())) {
+            Some(h) => h,
+            None => return false,
+        };
+
+        self.exempt_domains.iter().any(|domain| {
+            let domain = normalize_domain(domain);
+            !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+        })
+    }
+}
+
+/// Lowercase a domain and strip wildcard/leading-dot prefixes
+fn normalize_domain(domain: &str) -> String {
+    domain
+        .trim()
+        .trim_start_matches("*.")
+        .trim_start_matches('.')
+        .to_lowercase()
+}
+
+/// Load safety gate config from store
+pub fn load_gate_config<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> SafetyGateConfig {
+    let store = match app.store(STORE_PATH) {
+        Ok(s) => s,
+        Err(_) => return SafetyGateConfig::default(),
+    };
+
+    store
+        .get("config")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Save safety gate config to store
+pub fn save_gate_config<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    config: &SafetyGateConfig,
+) -> Result<(), String> {
+    if config.warning_threshold == 0 || config.daily_limit == 0 {
+        return Err("Daily limit and warning threshold must be greater than zero".to_string());
+    }
+    if config.warning_threshold >= config.daily_limit {
+        return Err("Warning threshold must be lower than the daily limit".to_string());
+    }
+
+    let mut config = config.clone();
+    config.exempt_domains = config
+        .exempt_domains
+        .iter()
+        .map(|d| normalize_domain(d))
+        .filter(|d| !d.is_empty())
+        .collect();
+
+    crate::settings::write_store(
+        app,
+        STORE_PATH,
+        "config",
+        serde_json::to_value(&config).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
+
 /// Load safety gate data from store
 pub fn load_gate_data<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> SafetyGateData {
+    load_checked(app).0
+}
+
+/// Load safety gate data, applying the daily reset, and report clock skew
+///
+/// A repair or reset is saved in the background. A detected jump is
+/// announced once with a `clock-skew` event.
+fn load_checked<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> (SafetyGateData, Option<ClockJump>) {
     let store = match app.store(STORE_PATH) {
         Ok(s) => s,
-        Err(_) => return SafetyGateData::default(),
+        Err(_) => return (SafetyGateData::default(), None),
     };
 
     let mut data: SafetyGateData = store
@@ -87,28 +283,80 @@ pub fn load_gate_data<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> SafetyGat
         .and_then(|v| serde_json::from_value(v).ok())
         .unwrap_or_default();
 
+    let repaired = data.repair();
+
     // Check for daily reset
-    data.check_daily_reset();
-    data
+    let count_date = data.count_date.clone();
+    let jump = data.check_daily_reset();
+    if repaired || data.count_date != count_date {
+        // Re-read under the store lock so a concurrent update isn't overwritten
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = update_gate_data(&app, |_| ()).await;
+        });
+    }
+
+    // A forward jump re-anchors the clock when detected, so it is only seen once
+    let newly_detected = match jump.as_ref().map(|jump| jump.direction) {
+        Some(ClockDirection::Backward) => {
+            let mut reported = REPORTED_BACKWARD_JUMP.lock().unwrap_or_else(|e| e.into_inner());
+            let new = *reported != data.last_seen;
+            *reported = data.last_seen;
+            new
+        }
+        Some(ClockDirection::Forward) => true,
+        None => false,
+    };
+    if newly_detected {
+        if let Some(jump) = &jump {
+            let _ = app.emit("clock-skew", jump.clone());
+        }
+    }
+    (data, jump)
 }
 
-/// Save safety gate data to store
-pub fn save_gate_data<R: tauri::Runtime>(
-    app: &tauri::AppHandle<R>,
-    data: &SafetyGateData,
-) -> Result<(), String> {
+/// Check the stored gate record and repair it if inconsistent
+///
+/// Returns whether a repair was needed. Loading the gate repairs it anyway;
+/// this lets diagnostics report it.
+pub async fn repair_gate_data<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<bool, String> {
     let store = app
         .store(STORE_PATH)
         .map_err(|e| format!("Failed to open store: {}", e))?;
 
-    store.set(
-        "safety_gate",
-        serde_json::to_value(data).map_err(|e| format!("Serialization error: {}", e))?,
-    );
+    let mut data: SafetyGateData = match store.get("safety_gate") {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("Corrupt gate record: {}", e))?,
+        None => return Ok(false),
+    };
+    if !data.repair() {
+        return Ok(false);
+    }
+    update_gate_data(app, |_| ()).await?;
+    Ok(true)
+}
 
-    store.save().map_err(|e| format!("Save error: {}", e))?;
+/// Check the system clock for changes that would affect the daily reset
+pub fn check_clock_skew<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Option<ClockJump> {
+    load_checked(app).1
+}
 
-    Ok(())
+/// Change the stored gate record under the store lock
+///
+/// The record is repaired and daily-reset first, exactly as when loading,
+/// so concurrent downloads each count against the latest saved value.
+async fn update_gate_data<R, U>(
+    app: &tauri::AppHandle<R>,
+    update: impl FnOnce(&mut SafetyGateData) -> U,
+) -> Result<U, String>
+where
+    R: tauri::Runtime,
+{
+    crate::settings::update_store(app, STORE_PATH, "safety_gate", |data: &mut SafetyGateData| {
+        data.repair();
+        let _ = data.check_daily_reset();
+        Ok(update(data))
+    })
+    .await
 }
 
 /// Get current download count
@@ -118,22 +366,47 @@ pub fn get_download_count<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> u32 {
 }
 
 /// Check if download should proceed
-pub fn should_allow_download<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> GateStatus {
+pub fn should_allow_download<R: tauri::Runtime>(app: &tauri::AppHandle<R>, url: &str) -> GateStatus {
+    let config = load_gate_config(app);
+    if config.is_exempt(url) {
+        return GateStatus::Open;
+    }
+
     let data = load_gate_data(app);
-    data.get_status()
+    data.get_status(&config)
 }
 
-/// Record a successful download
-pub fn record_download<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<u32, String> {
-    let mut data = load_gate_data(app);
-    data.increment();
-    save_gate_data(app, &data)?;
-    Ok(data.daily_count)
+/// Record the items a download actually fetched over the network
+///
+/// `completed` counts the entries yt-dlp wrote a destination for, so items that
+/// were skipped (already on disk or in the archive) are not counted, and
+/// neither are exempt hosts.
+pub async fn record_download<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &str,
+    completed: u32,
+) -> Result<u32, String> {
+    if completed == 0 || load_gate_config(app).is_exempt(url) {
+        return Ok(load_gate_data(app).daily_count);
+    }
+
+    update_gate_data(app, |data| {
+        data.increment(completed);
+        data.daily_count
+    })
+    .await
 }
 
 /// Set bypass mode
-pub fn set_bypass<R: tauri::Runtime>(app: &tauri::AppHandle<R>, enabled: bool) -> Result<(), String> {
-    let mut data = load_gate_data(app);
-    data.bypass_enabled = enabled;
-    save_gate_data(app, &data)
+pub async fn set_bypass<R: tauri::Runtime>(app: &tauri::AppHandle<R>, enabled: bool) -> Result<(), String> {
+    update_gate_data(app, |data| data.bypass_enabled = enabled).await
+}
+
+/// Clear a leftover bypass at launch unless it is configured to persist
+pub async fn reset_bypass_on_launch<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    if load_gate_config(app).persistent_bypass || !load_gate_data(app).bypass_enabled {
+        return Ok(());
+    }
+
+    update_gate_data(app, |data| data.bypass_enabled = false).await
 }
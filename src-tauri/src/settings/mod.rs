@@ -0,0 +1,215 @@
+//! General app settings
+//! Simple key/value flags persisted in settings.bin
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::OwnedMutexGuard;
+use tauri_plugin_store::{Store, StoreExt};
+
+use crate::state::AppState;
+
+pub const STORE_PATH: &str = "settings.bin";
+
+/// Key for toggling `download-resumed` events
+pub const EMIT_RESUME_EVENTS: &str = "emitResumeEvents";
+
+/// Key for the store save mode
+pub const STORE_SAVE_MODE: &str = "storeSaveMode";
+
+/// Key for the low-footprint mode (read once at startup)
+pub const LOW_FOOTPRINT: &str = "lowFootprint";
+
+/// Key for the number of log entries kept in memory
+pub const LOG_BUFFER_SIZE: &str = "logBufferSize";
+
+/// Key for the minimum gap between progress events of one download
+pub const PROGRESS_EVENT_INTERVAL_MS: &str = "progressEventIntervalMs";
+
+/// Key for how many queued downloads run at once
+pub const MAX_CONCURRENT_DOWNLOADS: &str = "maxConcurrentDownloads";
+
+/// Key for how often a download is retried after a network failure
+pub const DOWNLOAD_RETRIES: &str = "downloadRetries";
+
+/// Key for resuming partial files left by an interrupted download
+pub const RESUME_DOWNLOADS: &str = "resumeDownloads";
+
+/// Key for the user's yt-dlp output filename template
+pub const FILENAME_TEMPLATE: &str = "filenameTemplate";
+
+/// How config changes are written to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveMode {
+    /// Write to disk on every change
+    #[default]
+    Immediate,
+    /// Let the store's auto-save batch rapid changes into one write
+    Debounced,
+}
+
+/// Read a boolean setting, falling back to `default` when unset
+pub fn get_bool<R: tauri::Runtime>(app: &tauri::AppHandle<R>, key: &str, default: bool) -> bool {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(key))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(default)
+}
+
+/// Read an unsigned integer setting, falling back to `default` when unset
+pub fn get_u64<R: tauri::Runtime>(app: &tauri::AppHandle<R>, key: &str, default: u64) -> u64 {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(key))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(default)
+}
+
+/// Read a non-empty string setting
+pub fn get_string<R: tauri::Runtime>(app: &tauri::AppHandle<R>, key: &str) -> Option<String> {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(key))
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .filter(|s| !s.is_empty())
+}
+
+/// Persist a single setting value
+pub fn set_value<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    write_store(app, STORE_PATH, key, value)
+}
+
+/// One lock per store file, held across read-modify-write updates
+///
+/// Async mutexes: the guard stays held while the store is saved on a
+/// blocking thread.
+#[derive(Default)]
+pub struct StoreLocks {
+    locks: std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl StoreLocks {
+    /// Wait for the lock guarding the store at `path`
+    async fn lock(&self, path: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            locks.entry(path.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+/// Set `key` in the store at `path` and write it out
+///
+/// A plain overwrite for values that are replaced whole. Values that are
+/// read, changed and written back must go through `update_store` instead.
+pub fn write_store<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    path: &str,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let store = app
+        .store(path)
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    store.set(key, value);
+    persist(app, &store)
+}
+
+/// Read `key` from the store at `path`, change it and write it back
+///
+/// Runs under the store's lock, so concurrent updates to the same store
+/// never read a stale value and drop each other's changes. A missing or
+/// unreadable value starts from `T::default()`; an `Err` from `update`
+/// leaves the store untouched, and nothing is written when the value is
+/// unchanged.
+pub async fn update_store<R, T, U, F>(
+    app: &tauri::AppHandle<R>,
+    path: &str,
+    key: &str,
+    update: F,
+) -> Result<U, String>
+where
+    R: tauri::Runtime,
+    T: Serialize + DeserializeOwned + Default,
+    F: FnOnce(&mut T) -> Result<U, String>,
+{
+    // State isn't managed yet during the earliest part of setup
+    let _guard = match app.try_state::<AppState>() {
+        Some(state) => Some(state.store_locks.lock(path).await),
+        None => None,
+    };
+
+    let store = app
+        .store(path)
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    let before = store.get(key);
+    let (output, after) = {
+        let mut value: T = before
+            .clone()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        let output = update(&mut value)?;
+        let after = serde_json::to_value(&value).map_err(|e| format!("Serialization error: {}", e))?;
+        (output, after)
+    };
+    if before.as_ref() == Some(&after) {
+        return Ok(output);
+    }
+    store.set(key, after);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || persist(&app, &store))
+        .await
+        .map_err(|e| format!("Save error: {}", e))??;
+    Ok(output)
+}
+
+/// Get the configured store save mode
+pub fn load_save_mode<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> SaveMode {
+    app.store(STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(STORE_SAVE_MODE))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Write a store to disk according to the save mode
+///
+/// In debounced mode the store's auto-save (triggered by `set`) performs the
+/// write shortly after the last change, and `flush_all_stores` runs on exit.
+pub fn persist<R: tauri::Runtime>(app: &tauri::AppHandle<R>, store: &Store<R>) -> Result<(), String> {
+    if load_save_mode(app) == SaveMode::Debounced {
+        return Ok(());
+    }
+
+    store.save().map_err(|e| format!("Save error: {}", e))
+}
+
+/// Save every loaded store so nothing pending is lost on exit
+pub fn flush_all_stores<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let paths = [
+        STORE_PATH,
+        crate::anti_ban::STORE_PATH,
+        crate::history::STORE_PATH,
+        crate::proxy::STORE_PATH,
+        crate::queue::STORE_PATH,
+        crate::safety::STORE_PATH,
+    ];
+
+    for path in paths {
+        if let Some(store) = app.get_store(path) {
+            let _ = store.save();
+        }
+    }
+}
@@ -5,7 +5,11 @@ use std::path::PathBuf;
 use tauri::{Manager, Emitter};
 use thiserror::Error;
 use futures_util::StreamExt;
-use std::io::Write;
+use sha2::{Digest, Sha256};
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::network::STOPPED_MESSAGE;
+use crate::state::AppState;
 
 /// Errors that can occur during sidecar operations
 #[derive(Debug, Error, serde::Serialize)]
@@ -24,6 +28,9 @@ pub enum SidecarError {
 
     #[error("IO error: {0}")]
     IoError(String),
+
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
 }
 
 /// Sidecar binary type
@@ -70,6 +77,95 @@ impl SidecarType {
             }
         }
     }
+
+    /// Published SHA-256 list for the download and the asset name to look up in it
+    ///
+    /// None where the source publishes no checksum list (evermeet on macOS).
+    fn checksum_source(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            SidecarType::YtDlp => {
+                const SUMS: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/SHA2-256SUMS";
+                #[cfg(target_os = "windows")]
+                return Some((SUMS, "yt-dlp.exe"));
+                #[cfg(target_os = "linux")]
+                return Some((SUMS, "yt-dlp"));
+                #[cfg(target_os = "macos")]
+                return Some((SUMS, "yt-dlp_macos"));
+
+                #[allow(unreachable_code)]
+                {
+                    let _ = SUMS;
+                    None
+                }
+            }
+            SidecarType::Ffmpeg => {
+                #[cfg(target_os = "windows")]
+                return Some((
+                    "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/checksums.sha256",
+                    "ffmpeg-master-latest-win64-gpl.zip",
+                ));
+                #[cfg(target_os = "linux")]
+                return Some((
+                    "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/checksums.sha256",
+                    "ffmpeg-master-latest-linux64-gpl.tar.xz",
+                ));
+
+                #[allow(unreachable_code)]
+                None
+            }
+        }
+    }
+}
+
+/// Find the digest for `asset` in a `sha256sum`-style list
+fn parse_checksum(list: &str, asset: &str) -> Option<String> {
+    list.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let digest = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == asset).then(|| digest.to_ascii_lowercase())
+    })
+}
+
+/// Fetch the published digest for a sidecar download, if its source has one
+async fn expected_checksum(
+    client: &reqwest::Client,
+    sidecar_type: SidecarType,
+) -> Result<Option<String>, SidecarError> {
+    let Some((url, asset)) = sidecar_type.checksum_source() else {
+        return Ok(None);
+    };
+
+    let list = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| SidecarError::DownloadFailed(format!("Failed to fetch checksums: {}", e)))?
+        .text()
+        .await
+        .map_err(|e| SidecarError::DownloadFailed(format!("Failed to fetch checksums: {}", e)))?;
+
+    parse_checksum(&list, asset)
+        .map(Some)
+        .ok_or_else(|| SidecarError::ChecksumMismatch(format!("No checksum published for {}", asset)))
+}
+
+/// Compare a download's digest with the published one
+fn verify_checksum(
+    sidecar_type: SidecarType,
+    expected: Option<&str>,
+    actual: &str,
+) -> Result<(), SidecarError> {
+    match expected {
+        Some(expected) if expected != actual => Err(SidecarError::ChecksumMismatch(format!(
+            "{}: expected {}, got {}",
+            sidecar_type.base_name(),
+            expected,
+            actual
+        ))),
+        _ => Ok(()),
+    }
 }
 
 /// Get the current platform target triple
@@ -168,6 +264,108 @@ pub fn is_sidecar_available<R: tauri::Runtime>(
         .unwrap_or(false)
 }
 
+/// Captured output of a finished sidecar run
+#[derive(Debug, Clone, Default)]
+pub struct SidecarOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+impl SidecarOutput {
+    /// Whether the process exited with code 0
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Append a streamed output chunk, keeping one entry per line
+fn push_line(buffer: &mut String, bytes: &[u8]) {
+    buffer.push_str(&String::from_utf8_lossy(bytes));
+    if !buffer.ends_with('\n') {
+        buffer.push('\n');
+    }
+}
+
+/// Run a sidecar to completion and capture its stdout/stderr
+///
+/// When `timeout` is set, the child is killed once it elapses and
+/// `ExecutionFailed` is returned instead of partial output.
+pub async fn run_sidecar<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    sidecar_type: SidecarType,
+    args: &[String],
+    timeout: Option<std::time::Duration>,
+) -> Result<SidecarOutput, SidecarError> {
+    use tauri_plugin_shell::process::CommandEvent;
+    use tauri_plugin_shell::ShellExt;
+
+    // yt-dlp talks to the network; ffmpeg only ever touches local files
+    let network = &app.state::<AppState>().network;
+    let guarded = matches!(sidecar_type, SidecarType::YtDlp);
+    if guarded && network.is_stopped() {
+        return Err(SidecarError::ExecutionFailed(STOPPED_MESSAGE.to_string()));
+    }
+
+    let path = get_sidecar_path(app, sidecar_type)?;
+    if !path.exists() {
+        return Err(SidecarError::NotFound(path.to_string_lossy().to_string()));
+    }
+
+    let (mut rx, child) = app
+        .shell()
+        .command(path.to_string_lossy().to_string())
+        .args(args)
+        .spawn()
+        .map_err(|e| SidecarError::ExecutionFailed(e.to_string()))?;
+
+    let collect = async {
+        let mut output = SidecarOutput::default();
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => push_line(&mut output.stdout, &line),
+                CommandEvent::Stderr(line) => push_line(&mut output.stderr, &line),
+                CommandEvent::Terminated(status) => {
+                    output.code = status.code;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        output
+    };
+    // Resolves to None if a panic stop lands while the sidecar is running
+    let collect = async {
+        tokio::select! {
+            output = collect => Some(output),
+            _ = network.stopped(), if guarded => None,
+        }
+    };
+
+    let output = match timeout {
+        Some(limit) => match tokio::time::timeout(limit, collect).await {
+            Ok(output) => output,
+            Err(_) => {
+                let _ = child.kill();
+                return Err(SidecarError::ExecutionFailed(format!(
+                    "{} timed out after {}s",
+                    sidecar_type.base_name(),
+                    limit.as_secs()
+                )));
+            }
+        },
+        None => collect.await,
+    };
+
+    match output {
+        Some(output) => Ok(output),
+        None => {
+            let _ = child.kill();
+            Err(SidecarError::ExecutionFailed(STOPPED_MESSAGE.to_string()))
+        }
+    }
+}
+
 /// Download a sidecar binary with progress reporting
 /// 
 /// Uses a shared HTTP client from AppState for connection pooling
@@ -184,30 +382,25 @@ pub async fn download_binary<R: tauri::Runtime>(
         std::fs::create_dir_all(parent).map_err(|e| SidecarError::IoError(e.to_string()))?;
     }
 
-    let response = client.get(url).send().await.map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
-    
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
+    let expected = expected_checksum(client, sidecar_type).await?;
 
-    let mut file = std::fs::File::create(&path).map_err(|e| SidecarError::IoError(e.to_string()))?;
+    // Download next to the binary and only replace it once verified
+    let temp_path = path.with_extension("download");
+    let actual = fetch_to_file(app, client, sidecar_type, url, &temp_path, |fraction| {
+        let progress = fraction * 100.0;
+        let _ = app.emit("setup-progress", serde_json::json!({
+            "type": sidecar_type,
+            "progress": progress,
+            "status": format!("Downloading {}: {:.1}%", sidecar_type.base_name(), progress)
+        }));
+    })
+    .await?;
 
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
-        file.write_all(&chunk).map_err(|e| SidecarError::IoError(e.to_string()))?;
-        
-        downloaded += chunk.len() as u64;
-        
-        // Emit progress if possible
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64) * 100.0;
-            let _ = app.emit("setup-progress", serde_json::json!({
-                "type": sidecar_type,
-                "progress": progress,
-                "status": format!("Downloading {}: {:.1}%", sidecar_type.base_name(), progress)
-            }));
-        }
+    if let Err(e) = verify_checksum(sidecar_type, expected.as_deref(), &actual) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
     }
+    std::fs::rename(&temp_path, &path).map_err(|e| SidecarError::IoError(e.to_string()))?;
 
     // Set executable permissions on Unix
     #[cfg(unix)]
@@ -221,6 +414,156 @@ pub async fn download_binary<R: tauri::Runtime>(
     Ok(())
 }
 
+/// Attempts at a sidecar download before giving up
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry of a sidecar download; doubles for each one after
+const RETRY_BASE_DELAY_SECS: u64 = 2;
+
+/// Stream `url` into `dest`, retrying transient network errors
+///
+/// A partial `dest` left by an earlier attempt or install is kept: the
+/// request asks for the rest with a range and appends when the server answers
+/// 206, and starts over otherwise. `on_progress` gets the fraction done
+/// whenever the size is known. Returns the file's SHA-256; `dest` stays on
+/// network failures so the next install resumes it, and is removed otherwise.
+async fn fetch_to_file<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    client: &reqwest::Client,
+    sidecar_type: SidecarType,
+    url: &str,
+    dest: &std::path::Path,
+    on_progress: impl Fn(f64),
+) -> Result<String, SidecarError> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .truncate(false)
+        .open(dest)
+        .map_err(|e| SidecarError::IoError(e.to_string()))?;
+
+    // Bytes already on disk count towards the digest, and their length is
+    // where the transfer picks up
+    let mut hasher = Sha256::new();
+    let mut downloaded =
+        std::io::copy(&mut file, &mut hasher).map_err(|e| SidecarError::IoError(e.to_string()))?;
+    let network = &app.state::<AppState>().network;
+
+    let mut attempt = 1;
+    loop {
+        let result =
+            fetch_attempt(app, client, url, &mut file, &mut hasher, &mut downloaded, &on_progress).await;
+        let error = match result {
+            Ok(()) => break,
+            // Only network failures are worth another try
+            Err(SidecarError::DownloadFailed(e))
+                if attempt < DOWNLOAD_ATTEMPTS && !network.is_stopped() =>
+            {
+                e
+            }
+            // Keep what arrived so far for the next install
+            Err(e @ SidecarError::DownloadFailed(_)) => return Err(e),
+            Err(e) => {
+                drop(file);
+                let _ = std::fs::remove_file(dest);
+                return Err(e);
+            }
+        };
+
+        let delay_secs = RETRY_BASE_DELAY_SECS << (attempt - 1);
+        attempt += 1;
+        let _ = app.emit("setup-retry", serde_json::json!({
+            "type": sidecar_type,
+            "attempt": attempt,
+            "maxAttempts": DOWNLOAD_ATTEMPTS,
+            "delaySecs": delay_secs,
+            "error": error
+        }));
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(delay_secs)) => {}
+            _ = network.stopped() => {
+                return Err(SidecarError::DownloadFailed(STOPPED_MESSAGE.to_string()));
+            }
+        }
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// One request of `fetch_to_file`, appending to what earlier attempts wrote
+async fn fetch_attempt<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    client: &reqwest::Client,
+    url: &str,
+    file: &mut std::fs::File,
+    hasher: &mut Sha256,
+    downloaded: &mut u64,
+    on_progress: &impl Fn(f64),
+) -> Result<(), SidecarError> {
+    let mut request = client.get(url);
+    if *downloaded > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
+
+    let restart = |file: &mut std::fs::File, hasher: &mut Sha256, downloaded: &mut u64| {
+        file.set_len(0).map_err(|e| SidecarError::IoError(e.to_string()))?;
+        file.seek(SeekFrom::Start(0)).map_err(|e| SidecarError::IoError(e.to_string()))?;
+        *hasher = Sha256::new();
+        *downloaded = 0;
+        Ok::<(), SidecarError>(())
+    };
+
+    // The partial file is longer than the current upstream file, so it
+    // belongs to an older release; the retry starts from scratch
+    if *downloaded > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        restart(file, hasher, downloaded)?;
+        return Err(SidecarError::DownloadFailed(
+            "Partial download no longer matches the server; restarting".to_string(),
+        ));
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
+
+    // The server ignored the range and sent everything again
+    if *downloaded > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        restart(file, hasher, downloaded)?;
+    }
+
+    let expected_size = response.content_length().map(|len| len + *downloaded);
+    let total_size = expected_size.unwrap_or(0);
+    let mut stream = response.bytes_stream();
+
+    while let Some(item) = stream.next().await {
+        if app.state::<AppState>().network.is_stopped() {
+            return Err(SidecarError::DownloadFailed(STOPPED_MESSAGE.to_string()));
+        }
+        let chunk = item.map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
+        file.write_all(&chunk).map_err(|e| SidecarError::IoError(e.to_string()))?;
+        hasher.update(&chunk);
+
+        *downloaded += chunk.len() as u64;
+        if total_size > 0 {
+            on_progress(*downloaded as f64 / total_size as f64);
+        }
+    }
+
+    // A connection closed early can end the stream without an error
+    match expected_size {
+        Some(expected) if *downloaded != expected => Err(SidecarError::DownloadFailed(format!(
+            "Incomplete download: got {} of {} bytes",
+            downloaded, expected
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Download and extract FFmpeg binaries from archive
 /// 
 /// FFmpeg is distributed as a ZIP/TAR archive containing multiple binaries.
@@ -252,29 +595,23 @@ pub async fn download_ffmpeg<R: tauri::Runtime>(
     }));
 
     // Download to temp file
-    let response = client.get(url).send().await.map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
-    
+    let expected = expected_checksum(client, SidecarType::Ffmpeg).await?;
     let temp_path = std::env::temp_dir().join("ffmpeg_download.zip");
-    let mut file = std::fs::File::create(&temp_path).map_err(|e| SidecarError::IoError(e.to_string()))?;
-    
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| SidecarError::DownloadFailed(e.to_string()))?;
-        file.write_all(&chunk).map_err(|e| SidecarError::IoError(e.to_string()))?;
-        
-        downloaded += chunk.len() as u64;
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64) * 50.0; // 0-50% for download
-            let _ = app.emit("setup-progress", serde_json::json!({
-                "type": "ffmpeg",
-                "progress": progress,
-                "status": format!("Downloading ffmpeg: {:.1}%", progress * 2.0)
-            }));
-        }
+    let actual = fetch_to_file(app, client, SidecarType::Ffmpeg, url, &temp_path, |fraction| {
+        let progress = fraction * 50.0; // 0-50% for download
+        let _ = app.emit("setup-progress", serde_json::json!({
+            "type": "ffmpeg",
+            "progress": progress,
+            "status": format!("Downloading ffmpeg: {:.1}%", progress * 2.0)
+        }));
+    })
+    .await?;
+
+    // Never extract an archive that doesn't match the published digest
+    if let Err(e) = verify_checksum(SidecarType::Ffmpeg, expected.as_deref(), &actual) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
     }
-    drop(file);
     
     // Emit extraction status
     let _ = app.emit("setup-progress", serde_json::json!({
@@ -356,3 +693,47 @@ pub async fn download_ffmpeg<R: tauri::Runtime>(
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// sha256 of `b"abc"`
+    const ABC_DIGEST: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn digest_of(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    #[test]
+    fn fixture_matches_its_published_checksum() {
+        let list = format!(
+            "{}  yt-dlp.exe\n{}  yt-dlp\n",
+            "0".repeat(64),
+            ABC_DIGEST.to_ascii_uppercase()
+        );
+        let expected = parse_checksum(&list, "yt-dlp").expect("checksum listed");
+        assert_eq!(expected, ABC_DIGEST);
+        assert!(verify_checksum(SidecarType::YtDlp, Some(&expected), &digest_of(b"abc")).is_ok());
+    }
+
+    #[test]
+    fn binary_marker_is_ignored() {
+        let list = format!("{} *ffmpeg-release.zip\n", ABC_DIGEST);
+        assert_eq!(parse_checksum(&list, "ffmpeg-release.zip").as_deref(), Some(ABC_DIGEST));
+    }
+
+    #[test]
+    fn missing_asset_has_no_checksum() {
+        let list = format!("{}  yt-dlp\n", ABC_DIGEST);
+        assert_eq!(parse_checksum(&list, "yt-dlp_macos"), None);
+    }
+
+    #[test]
+    fn tampered_download_is_a_mismatch() {
+        let list = format!("{}  yt-dlp\n", ABC_DIGEST);
+        let expected = parse_checksum(&list, "yt-dlp");
+        let result = verify_checksum(SidecarType::YtDlp, expected.as_deref(), &digest_of(b"abd"));
+        assert!(matches!(result, Err(SidecarError::ChecksumMismatch(_))));
+    }
+}
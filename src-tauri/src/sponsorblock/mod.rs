@@ -0,0 +1,121 @@
+//! SponsorBlock segment removal
+//! Cuts community-marked segments (sponsors, intros, reminders) out of YouTube downloads
+
+use serde::{Deserialize, Serialize};
+
+/// Settings key holding the SponsorBlock config
+const SPONSORBLOCK_KEY: &str = "sponsorBlock";
+
+/// Segment categories yt-dlp can remove (`poi_highlight` and `chapter` only mark points)
+pub const REMOVABLE_CATEGORIES: &[&str] = &[
+    "sponsor",
+    "intro",
+    "outro",
+    "selfpromo",
+    "preview",
+    "filler",
+    "interaction",
+    "music_offtopic",
+];
+
+/// Which SponsorBlock segments to cut from downloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SponsorBlockConfig {
+    pub enabled: bool,
+    /// Categories from `REMOVABLE_CATEGORIES`
+    pub categories: Vec<String>,
+}
+
+impl Default for SponsorBlockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            // Ads and "like and subscribe" reminders, never actual content
+            categories: vec![
+                "sponsor".to_string(),
+                "selfpromo".to_string(),
+                "interaction".to_string(),
+            ],
+        }
+    }
+}
+
+impl SponsorBlockConfig {
+    /// Whether downloads will have segments cut out
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.categories.is_empty()
+    }
+
+    /// Build yt-dlp SponsorBlock arguments
+    pub fn to_ytdlp_args(&self) -> Vec<String> {
+        if !self.is_active() {
+            return vec![];
+        }
+        vec![
+            "--sponsorblock-remove".to_string(),
+            self.categories.join(","),
+        ]
+    }
+}
+
+/// Reject categories yt-dlp can't remove
+pub fn validate_categories(categories: &[String]) -> Result<(), String> {
+    match categories
+        .iter()
+        .find(|c| !REMOVABLE_CATEGORIES.contains(&c.as_str()))
+    {
+        Some(unknown) => Err(format!("Unknown SponsorBlock category: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+/// Config for one download: its own categories when it names any, else the saved config
+pub fn effective_config<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    requested: &[String],
+) -> SponsorBlockConfig {
+    if requested.is_empty() {
+        load_config(app)
+    } else {
+        SponsorBlockConfig {
+            enabled: true,
+            categories: requested.to_vec(),
+        }
+    }
+}
+
+/// Load the SponsorBlock config from the settings store
+pub fn load_config<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> SponsorBlockConfig {
+    use tauri_plugin_store::StoreExt;
+
+    app.store(crate::settings::STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(SPONSORBLOCK_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the SponsorBlock config
+pub fn save_config<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    config: &SponsorBlockConfig,
+) -> Result<(), String> {
+    let mut config = config.clone();
+    config.categories = config
+        .categories
+        .iter()
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect();
+    config.categories.sort();
+    config.categories.dedup();
+
+    validate_categories(&config.categories)?;
+
+    crate::settings::set_value(
+        app,
+        SPONSORBLOCK_KEY,
+        serde_json::to_value(&config).map_err(|e| format!("Serialization error: {}", e))?,
+    )
+}
@@ -3,6 +3,19 @@
 
 use reqwest::Client;
 
+use crate::backoff::UrlCooldowns;
+use crate::cache::InfoCache;
+use crate::cancel::ActiveDownloads;
+use crate::errors::ErrorHistory;
+use crate::events::EventThrottle;
+use crate::history::SessionDownloads;
+use crate::logs::LogBuffer;
+use crate::network::NetworkGuard;
+use crate::power::PowerPause;
+use crate::proxy::ProxyRotator;
+use crate::queue::DownloadQueue;
+use crate::settings::StoreLocks;
+
 /// Global application state accessible from commands via `tauri::State`
 /// 
 /// Following Tauri architecture best practices, shared resources like
@@ -13,14 +26,41 @@ use reqwest::Client;
 pub struct AppState {
     /// Shared HTTP client with connection pooling
     pub http_client: Client,
+    /// Cooldowns for URLs that recently failed
+    pub url_cooldowns: UrlCooldowns,
+    /// Pending and running queued downloads
+    pub queue: DownloadQueue,
+    /// Recently fetched yt-dlp info JSON
+    pub info_cache: InfoCache,
+    /// Coalesces per-download progress events
+    pub event_throttle: EventThrottle,
+    /// Downloads completed since launch
+    pub session_downloads: SessionDownloads,
+    /// Recent `download-log` messages
+    pub logs: LogBuffer,
+    /// Kill switch for all network activity
+    pub network: NetworkGuard,
+    /// yt-dlp processes of running downloads, for cancellation
+    pub active_downloads: ActiveDownloads,
+    /// Recent download failures by category
+    pub recent_errors: ErrorHistory,
+    /// Proxy pool rotation cursor and dead proxies
+    pub proxy_rotator: ProxyRotator,
+    /// Queue pauses caused by battery or metered-connection state
+    pub power_pause: PowerPause,
+    /// Serializes read-modify-write updates of each store file
+    pub store_locks: StoreLocks,
 }
 
 impl AppState {
     /// Create a new AppState with optimized HTTP client settings
-    pub fn new() -> Self {
+    ///
+    /// `low_footprint` trades speed for memory: no idle pooled connections,
+    /// no metadata cache and a small log buffer.
+    pub fn new(low_footprint: bool) -> Self {
         let http_client = Client::builder()
             // Keep 5 idle connections per host for reuse
-            .pool_max_idle_per_host(5)
+            .pool_max_idle_per_host(if low_footprint { 0 } else { 5 })
             // 5 minute timeout for large file downloads (ffmpeg is ~80MB)
             .timeout(std::time::Duration::from_secs(300))
             // User-Agent required for GitHub downloads
@@ -29,12 +69,34 @@ impl AppState {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { http_client }
+        Self {
+            http_client,
+            url_cooldowns: UrlCooldowns::default(),
+            queue: DownloadQueue::default(),
+            info_cache: if low_footprint {
+                InfoCache::with_capacity(0)
+            } else {
+                InfoCache::default()
+            },
+            event_throttle: EventThrottle::default(),
+            session_downloads: SessionDownloads::default(),
+            network: NetworkGuard::default(),
+            active_downloads: ActiveDownloads::default(),
+            recent_errors: ErrorHistory::default(),
+            proxy_rotator: ProxyRotator::default(),
+            power_pause: PowerPause::default(),
+            store_locks: StoreLocks::default(),
+            logs: LogBuffer::with_capacity(if low_footprint {
+                crate::logs::LOW_FOOTPRINT_CAPACITY
+            } else {
+                crate::logs::DEFAULT_CAPACITY
+            }),
+        }
     }
 }
 
 impl Default for AppState {
     fn default() -> Self {
-        Self::new()
+        Self::new(false)
     }
 }
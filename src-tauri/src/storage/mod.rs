@@ -0,0 +1,100 @@
+//! Storage volume detection
+//! Classifies paths as local, removable or network so missing drives can be reported
+
+use serde::Serialize;
+use std::path::Path;
+
+/// Filesystem types that live on another machine
+#[cfg(target_os = "linux")]
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "afs", "9p", "fuse.sshfs", "fuse.rclone", "davfs",
+];
+
+/// Where a path's volume lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeKind {
+    Local,
+    Removable,
+    Network,
+}
+
+impl VolumeKind {
+    /// Whether the volume can disappear while the app is running
+    pub fn is_detachable(&self) -> bool {
+        !matches!(self, VolumeKind::Local)
+    }
+}
+
+/// Classify the volume a path lives on
+///
+/// Works for paths that are currently missing too, falling back to where
+/// removable and network volumes are conventionally mounted.
+pub fn volume_kind(path: &Path) -> VolumeKind {
+    #[cfg(target_os = "windows")]
+    {
+        // UNC paths (\\server\share) are always network shares
+        if path.to_string_lossy().starts_with(r"\\") {
+            return VolumeKind::Network;
+        }
+        // A drive letter that no longer resolves was most likely unplugged
+        if let Some(root) = path.ancestors().last() {
+            if !root.exists() {
+                return VolumeKind::Removable;
+            }
+        }
+        VolumeKind::Local
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Everything but the boot volume is mounted under /Volumes
+        if path.starts_with("/Volumes") {
+            VolumeKind::Removable
+        } else {
+            VolumeKind::Local
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(fs_type) = mount_fs_type(path) {
+            if NETWORK_FS_TYPES.contains(&fs_type.as_str()) {
+                return VolumeKind::Network;
+            }
+        }
+        if path.starts_with("/media") || path.starts_with("/run/media") || path.starts_with("/mnt") {
+            VolumeKind::Removable
+        } else {
+            VolumeKind::Local
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = path;
+        VolumeKind::Local
+    }
+}
+
+/// Filesystem type of the most specific mount containing `path`
+#[cfg(target_os = "linux")]
+fn mount_fs_type(path: &Path) -> Option<String> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        // The root mount matches everything and says nothing about the path
+        .filter(|(len, _)| *len > 1)
+        .map(|(_, fs_type)| fs_type)
+}